@@ -0,0 +1,239 @@
+//! Derive macros for [plain-old-pointer](https://docs.rs/plain-old-pointer).
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::format_ident;
+use quote::quote;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Index;
+use syn::Member;
+use syn::Visibility;
+
+/// The inherent methods of `pop::ptr<T>`. A trait method with one of these
+/// names would be silently shadowed, so such fields are rejected. Keep this
+/// in sync with the `pop` crate.
+
+const PTR_METHODS: &[&str] = &[
+  "addr",
+  "as_const_ptr",
+  "as_mut_ptr",
+  "as_mut_ref",
+  "as_non_null",
+  "as_ref",
+  "as_slice_const_ptr",
+  "as_slice_mut_ptr",
+  "as_slice_mut_ref",
+  "as_slice_non_null",
+  "as_slice_ref",
+  "byte_add",
+  "byte_compare",
+  "byte_copy_from",
+  "byte_copy_from_nonoverlapping",
+  "byte_copy_to",
+  "byte_copy_to_nonoverlapping",
+  "byte_diff",
+  "byte_fill",
+  "byte_read_volatile_to",
+  "byte_sub",
+  "byte_swap_nonoverlapping",
+  "byte_write_volatile_from",
+  "cast",
+  "compare_bytes",
+  "copy_from",
+  "copy_from_nonoverlapping",
+  "copy_to",
+  "copy_to_nonoverlapping",
+  "drop_in_place",
+  "fill",
+  "invalid",
+  "is_aligned",
+  "is_null",
+  "read",
+  "read_unaligned",
+  "read_volatile",
+  "read_volatile_to",
+  "replace",
+  "swap",
+  "swap_nonoverlapping",
+  "with_addr",
+  "write",
+  "write_bytes",
+  "write_unaligned",
+  "write_volatile",
+  "write_volatile_from",
+];
+
+/// Generates typed field accessors on `ptr<S>`.
+///
+/// For a struct `S` and each field `f: F`, this generates
+///
+/// - a method `f(self) -> ptr<F>` on `ptr<S>`, by way of a trait defined
+///   next to `S`, and
+/// - an associated function `S::from_f(ptr<F>) -> ptr<S>` that recovers the
+///   container.
+///
+/// Tuple struct fields are named `_0`, `_1`, ..., with `from_0`, `from_1`,
+/// ....
+///
+/// Fields that are `pub` or as visible as the struct get their methods from
+/// a trait named `SFields`, which has the visibility of the struct. The
+/// remaining fields get theirs from a private trait named `SPrivateFields`,
+/// so that the accessors are never more visible than the fields. Each
+/// associated function has the visibility of its field.
+///
+/// A field whose name is an inherent method of `ptr`, such as `read` or
+/// `cast`, is a compile error, because the method would shadow the accessor.
+///
+/// The generated code refers to the `pop` crate by name.
+///
+/// ```compile_fail
+/// #[derive(pop::Fields)]
+/// struct S { read: u32 }
+/// ```
+///
+/// ```compile_fail
+/// mod m {
+///   #[derive(pop::Fields)]
+///   pub struct S { pub a: u32, b: u32 }
+/// }
+///
+/// use m::SFields;
+///
+/// fn f(p: pop::ptr<m::S>) -> pop::ptr<u32> {
+///   return p.b();
+/// }
+/// ```
+
+#[proc_macro_derive(Fields)]
+pub fn derive_fields(input: TokenStream) -> TokenStream {
+  let input = syn::parse_macro_input!(input as DeriveInput);
+
+  return match fields(&input) {
+    Ok(x) => x.into(),
+    Err(e) => e.to_compile_error().into(),
+  };
+}
+
+fn fields(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+  let fields =
+    match &input.data {
+      Data::Struct(x) => &x.fields,
+      _ => return Err(syn::Error::new(Span::call_site(), "`Fields` can only be derived for structs")),
+    };
+
+  let vis = &input.vis;
+  let name = &input.ident;
+  let trait_name = format_ident!("{}Fields", name);
+  let private_trait_name = format_ident!("{}PrivateFields", name);
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+  let vis_string = quote!(#vis).to_string();
+
+  let mut decls = Vec::new();
+  let mut impls = Vec::new();
+  let mut private_decls = Vec::new();
+  let mut private_impls = Vec::new();
+  let mut froms = Vec::new();
+  let mut errors: Option<syn::Error> = None;
+
+  for (i, field) in fields.iter().enumerate() {
+    let (member, method, from) =
+      match &field.ident {
+        Some(ident) => (Member::Named(ident.clone()), ident.clone(), format_ident!("from_{}", ident)),
+        None => (Member::Unnamed(Index::from(i)), format_ident!("_{}", i), format_ident!("from_{}", i)),
+      };
+
+    if PTR_METHODS.contains(&method.to_string().as_str()) {
+      let e =
+        syn::Error::new_spanned(
+          &method,
+          format!("field `{}` would be shadowed by the inherent method `ptr::{}`", method, method)
+        );
+
+      match &mut errors {
+        Some(x) => x.combine(e),
+        None => errors = Some(e),
+      }
+
+      continue;
+    }
+
+    let field_vis = &field.vis;
+    let ty = &field.ty;
+    let doc = format!("A pointer to the `{}` field.", quote!(#member));
+    let from_doc = format!("Recovers a pointer to the container from a pointer to the `{}` field.", quote!(#member));
+
+    let decl = quote! {
+      #[doc = #doc]
+      fn #method(self) -> ::pop::ptr<#ty>;
+    };
+
+    let imp = quote! {
+      #[inline(always)]
+      fn #method(self) -> ::pop::ptr<#ty> {
+        return self.byte_add(::core::mem::offset_of!(#name #ty_generics, #member));
+      }
+    };
+
+    let public =
+      matches!(input.vis, Visibility::Inherited)
+        || matches!(field.vis, Visibility::Public(_))
+        || quote!(#field_vis).to_string() == vis_string;
+
+    if public {
+      decls.push(decl);
+      impls.push(imp);
+    } else {
+      private_decls.push(decl);
+      private_impls.push(imp);
+    }
+
+    froms.push(quote! {
+      #[doc = #from_doc]
+      #[inline(always)]
+      #field_vis fn #from(x: ::pop::ptr<#ty>) -> ::pop::ptr<Self> {
+        return x.byte_sub(::core::mem::offset_of!(Self, #member));
+      }
+    });
+  }
+
+  if let Some(e) = errors {
+    return Err(e);
+  }
+
+  let trait_doc = format!("Field accessors on `ptr<{}>`.", name);
+  let private_trait_doc = format!("Accessors on `ptr<{}>` for fields less visible than the struct.", name);
+
+  let private_trait =
+    if private_decls.is_empty() {
+      quote! {}
+    } else {
+      quote! {
+        #[doc = #private_trait_doc]
+        trait #private_trait_name #impl_generics #where_clause {
+          #(#private_decls)*
+        }
+
+        impl #impl_generics #private_trait_name #ty_generics for ::pop::ptr<#name #ty_generics> #where_clause {
+          #(#private_impls)*
+        }
+      }
+    };
+
+  return Ok(quote! {
+    #[doc = #trait_doc]
+    #vis trait #trait_name #impl_generics #where_clause {
+      #(#decls)*
+    }
+
+    impl #impl_generics #trait_name #ty_generics for ::pop::ptr<#name #ty_generics> #where_clause {
+      #(#impls)*
+    }
+
+    #private_trait
+
+    impl #impl_generics #name #ty_generics #where_clause {
+      #(#froms)*
+    }
+  });
+}
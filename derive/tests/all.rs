@@ -0,0 +1,53 @@
+//! Tests
+
+use pop::ptr;
+
+#[test]
+fn test_fields() {
+  #[derive(pop::Fields)]
+  struct Node<T> {
+    value: T,
+    link: (usize, usize),
+  }
+
+  #[derive(pop::Fields)]
+  struct Pair(u8, u64);
+
+  let mut x = Node { value: 1u32, link: (2, 3) };
+  let p = ptr::from(&mut x);
+  assert_eq!(unsafe { p.value().read() }, 1);
+  assert_eq!(unsafe { p.link().read() }, (2, 3));
+  assert_eq!(Node::from_link(p.link()), p);
+  assert_eq!(Node::<u32>::from_value(ptr::from(&mut x.value)), p);
+
+  let y = Pair(4, 5);
+  let q = ptr::from(&y);
+  assert_eq!(unsafe { q._1().read() }, 5);
+  assert_eq!(Pair::from_0(q._0()), q);
+
+  mod m {
+    use pop::ptr;
+
+    #[derive(pop::Fields)]
+    pub(crate) struct Secret {
+      pub(crate) open: u32,
+      hidden: u64,
+    }
+
+    pub(crate) fn hidden(p: ptr<Secret>) -> ptr<u64> {
+      return p.hidden();
+    }
+
+    pub(crate) fn new() -> Secret {
+      return Secret { open: 6, hidden: 7 };
+    }
+  }
+
+  use m::SecretFields;
+
+  let z = m::new();
+  let r = ptr::from(&z);
+  assert_eq!(unsafe { r.open().read() }, 6);
+  assert_eq!(unsafe { m::hidden(r).read() }, 7);
+  assert_eq!(m::Secret::from_open(r.open()), r);
+}
@@ -362,6 +362,227 @@ impl<T> ptr<T> {
   pub const unsafe fn write_bytes(self, value: u8, count: usize) {
     unsafe { core::ptr::write_bytes(self.0 as *mut T, value, count) };
   }
+
+  /// Copies `count * size_of::<T>()` bytes from `src` to `self`. The source
+  /// and destination regions may overlap.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::copy].
+
+  #[inline(always)]
+  pub const unsafe fn copy_from(self, src: ptr<T>, count: usize) {
+    unsafe { core::ptr::copy(src.0 as *const T, self.0 as *mut T, count) };
+  }
+
+  /// Copies `count * size_of::<T>()` bytes from `self` to `dst`. The source
+  /// and destination regions may overlap.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::copy].
+
+  #[inline(always)]
+  pub const unsafe fn copy_to(self, dst: ptr<T>, count: usize) {
+    unsafe { core::ptr::copy(self.0 as *const T, dst.0 as *mut T, count) };
+  }
+
+  /// Copies `count * size_of::<T>()` bytes from `self` to `dst`. The source
+  /// and destination regions must not overlap.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::copy_nonoverlapping].
+
+  #[inline(always)]
+  pub const unsafe fn copy_to_nonoverlapping(self, dst: ptr<T>, count: usize) {
+    unsafe { core::ptr::copy_nonoverlapping(self.0 as *const T, dst.0 as *mut T, count) };
+  }
+
+  /// Swaps the values at `self` and `with`, which may be equal.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::swap].
+
+  #[inline(always)]
+  pub const unsafe fn swap(self, with: ptr<T>) {
+    unsafe { core::ptr::swap(self.0 as *mut T, with.0 as *mut T) };
+  }
+
+  /// Writes `count` clones of `value` at `self`, moving `value` into the
+  /// last slot. The destination is overwritten without dropping it. If a
+  /// clone panics, the values written so far are leaked.
+  ///
+  /// # SAFETY
+  ///
+  /// The region must be valid for writes of `count` values of `T` and
+  /// properly aligned.
+
+  pub unsafe fn fill(self, value: T, count: usize) where T: Clone {
+    if count == 0 {
+      return;
+    }
+
+    for i in 0 .. count - 1 {
+      unsafe { (self + i).write(value.clone()) };
+    }
+
+    unsafe { (self + (count - 1)).write(value) };
+  }
+
+  /// Lexicographically compares `count * size_of::<T>()` bytes at `self`
+  /// with those at `other`.
+  ///
+  /// # SAFETY
+  ///
+  /// Both regions must be valid for reads and their bytes must be
+  /// initialized. In particular, `T` must not have padding.
+
+  #[inline(always)]
+  pub unsafe fn compare_bytes(self, other: ptr<T>, count: usize) -> core::cmp::Ordering {
+    return unsafe { self.byte_compare(other, count * size_of::<T>()) };
+  }
+
+  /// Reads `count` values from `self` with volatile loads and writes them to
+  /// `dst` with ordinary stores.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::read_volatile] and [core::ptr::write], for each of the
+  /// `count` values.
+
+  #[inline(always)]
+  pub unsafe fn read_volatile_to(self, dst: ptr<T>, count: usize) {
+    for i in 0 .. count {
+      unsafe { (dst + i).write((self + i).read_volatile()) };
+    }
+  }
+
+  /// Reads `count` values from `src` with ordinary loads and writes them to
+  /// `self` with volatile stores.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::read] and [core::ptr::write_volatile], for each of the
+  /// `count` values.
+
+  #[inline(always)]
+  pub unsafe fn write_volatile_from(self, src: ptr<T>, count: usize) {
+    for i in 0 .. count {
+      unsafe { (self + i).write_volatile((src + i).read()) };
+    }
+  }
+
+  /// Copies `n` bytes from `src` to `self`. The source and destination
+  /// regions may overlap.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::copy].
+
+  #[inline(always)]
+  pub const unsafe fn byte_copy_from<U>(self, src: ptr<U>, n: usize) {
+    unsafe { self.cast::<u8>().copy_from(src.cast(), n) };
+  }
+
+  /// Copies `n` bytes from `self` to `dst`. The source and destination
+  /// regions may overlap.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::copy].
+
+  #[inline(always)]
+  pub const unsafe fn byte_copy_to<U>(self, dst: ptr<U>, n: usize) {
+    unsafe { self.cast::<u8>().copy_to(dst.cast(), n) };
+  }
+
+  /// Copies `n` bytes from `src` to `self`. The source and destination
+  /// regions must not overlap.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::copy_nonoverlapping].
+
+  #[inline(always)]
+  pub const unsafe fn byte_copy_from_nonoverlapping<U>(self, src: ptr<U>, n: usize) {
+    unsafe { self.cast::<u8>().copy_from_nonoverlapping(src.cast(), n) };
+  }
+
+  /// Copies `n` bytes from `self` to `dst`. The source and destination
+  /// regions must not overlap.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::copy_nonoverlapping].
+
+  #[inline(always)]
+  pub const unsafe fn byte_copy_to_nonoverlapping<U>(self, dst: ptr<U>, n: usize) {
+    unsafe { self.cast::<u8>().copy_to_nonoverlapping(dst.cast(), n) };
+  }
+
+  /// Swaps `n` bytes between the regions pointed-to by `self` and `with`.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::swap_nonoverlapping].
+
+  #[inline(always)]
+  pub const unsafe fn byte_swap_nonoverlapping<U>(self, with: ptr<U>, n: usize) {
+    unsafe { self.cast::<u8>().swap_nonoverlapping(with.cast(), n) };
+  }
+
+  /// Writes `n` copies of byte `value` at `self`.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::write_bytes].
+
+  #[inline(always)]
+  pub const unsafe fn byte_fill(self, value: u8, n: usize) {
+    unsafe { self.cast::<u8>().write_bytes(value, n) };
+  }
+
+  /// Lexicographically compares `n` bytes at `self` with those at `other`.
+  ///
+  /// # SAFETY
+  ///
+  /// Both regions must be valid for reads and their bytes must be
+  /// initialized.
+
+  #[inline(always)]
+  pub unsafe fn byte_compare<U>(self, other: ptr<U>, n: usize) -> core::cmp::Ordering {
+    let x = unsafe { self.cast::<u8>().as_slice_ref(n) };
+    let y = unsafe { other.cast::<u8>().as_slice_ref(n) };
+    return x.cmp(y);
+  }
+
+  /// Reads `n` bytes from `self` with volatile loads and writes them to
+  /// `dst` with ordinary stores.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::read_volatile] and [core::ptr::write], for each of the
+  /// `n` bytes.
+
+  #[inline(always)]
+  pub unsafe fn byte_read_volatile_to<U>(self, dst: ptr<U>, n: usize) {
+    unsafe { self.cast::<u8>().read_volatile_to(dst.cast(), n) };
+  }
+
+  /// Reads `n` bytes from `src` with ordinary loads and writes them to
+  /// `self` with volatile stores.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::read] and [core::ptr::write_volatile], for each of the
+  /// `n` bytes.
+
+  #[inline(always)]
+  pub unsafe fn byte_write_volatile_from<U>(self, src: ptr<U>, n: usize) {
+    unsafe { self.cast::<u8>().write_volatile_from(src.cast(), n) };
+  }
 }
 
 impl<T> From<*const T> for ptr<T> {
@@ -668,205 +889,7652 @@ impl<T> core::fmt::Debug for ptr<T> {
   }
 }
 
-#[cfg(feature = "alloc")]
-pub mod global {
-  //! TODO
+/// A non-null [`ptr`].
+///
+/// Like `ptr<T>`, an `nptr<T>` is a plain address with provenance and no
+/// other invariants, except that its address is never zero. In exchange,
+/// `Option<nptr<T>>` is the same size as `nptr<T>`.
+///
+/// Arithmetic wraps around like `ptr`'s, and panics if the result would have
+/// address zero.
 
-  extern crate alloc;
+#[allow(non_camel_case_types)]
+#[repr(transparent)]
+pub struct nptr<T>(NonNull<u8>, PhantomData<fn(T) -> T>);
 
-  use core::alloc::Layout;
-  use super::ptr;
+unsafe impl<T> Send for nptr<T> {
+}
 
-  /// Allocates memory with the global allocator.
-  ///
-  /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
-  /// return.
-  ///
-  /// # SAFETY
-  ///
-  /// See [alloc::alloc::GlobalAlloc::alloc].
+unsafe impl<T> Sync for nptr<T> {
+}
 
-  pub unsafe fn alloc_layout<T>(layout: Layout) -> ptr<T> {
-    let x = unsafe { alloc::alloc::alloc(layout) };
-    let x = ptr::from(x).cast();
+impl<T> Clone for nptr<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    return *self;
+  }
+}
 
-    if x.is_null() {
-      match alloc::alloc::handle_alloc_error(layout) {
-      }
-    }
+impl<T> Copy for nptr<T> {
+}
 
-    return x;
+impl<T> Eq for nptr<T> {
+}
+
+impl<T> core::hash::Hash for nptr<T> {
+  #[inline(always)]
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.0.hash(state);
   }
+}
 
-  /// Allocates zero-initialized memory with the global allocator.
-  ///
-  /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
-  /// return.
-  ///
-  /// # SAFETY
-  ///
-  /// See [alloc::alloc::GlobalAlloc::alloc_zeroed].
+impl<T> Ord for nptr<T> {
+  #[inline(always)]
+  fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+    return self.0.cmp(&other.0);
+  }
+}
 
-  pub unsafe fn alloc_layout_zeroed<T>(layout: Layout) -> ptr<T> {
-    let x = unsafe { alloc::alloc::alloc_zeroed(layout) };
-    let x = ptr::from(x).cast();
+impl<T> PartialEq for nptr<T> {
+  #[inline(always)]
+  fn eq(&self, other: &Self) -> bool {
+    return self.0 == other.0;
+  }
 
-    if x.is_null() {
-      match alloc::alloc::handle_alloc_error(layout) {
-      }
-    }
+  #[inline(always)]
+  fn ne(&self, other: &Self) -> bool {
+    return self.0 != other.0;
+  }
+}
 
-    return x;
+impl<T> PartialOrd for nptr<T> {
+  #[inline(always)]
+  fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+    return self.0.partial_cmp(&other.0);
   }
 
-  /// Allocates memory for a `T` with the global allocator.
-  ///
-  /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
-  /// return.
-  ///
-  /// # SAFETY
-  ///
-  /// `T` must have non-zero size.
+  #[inline(always)]
+  fn lt(&self, other: &Self) -> bool {
+    return self.0 < other.0;
+  }
 
-  pub unsafe fn alloc<T>() -> ptr<T> {
-    debug_assert!(size_of::<T>() != 0);
+  #[inline(always)]
+  fn le(&self, other: &Self) -> bool {
+    return self.0 <= other.0;
+  }
 
-    return unsafe { alloc_layout(Layout::new::<T>()) };
+  #[inline(always)]
+  fn gt(&self, other: &Self) -> bool {
+    return self.0 > other.0;
   }
 
-  /// Allocates zero-initialized memory for a `T` with the global allocator.
-  ///
-  /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
-  /// return.
-  ///
-  /// # SAFETY
-  ///
-  /// `T` must have non-zero size.
+  #[inline(always)]
+  fn ge(&self, other: &Self) -> bool {
+    return self.0 >= other.0;
+  }
+}
 
-  pub unsafe fn alloc_zeroed<T>() -> ptr<T> {
-    debug_assert!(size_of::<T>() != 0);
+#[cold]
+#[inline(never)]
+fn panic_null() -> ! {
+  panic!("pointer arithmetic produced a null pointer");
+}
 
-    return unsafe { alloc_layout_zeroed(Layout::new::<T>()) };
+impl<T> nptr<T> {
+  /// Converts a `ptr<T>`, returning `None` if its address is zero.
+
+  #[inline(always)]
+  pub const fn new(x: ptr<T>) -> Option<nptr<T>> {
+    return match NonNull::new(x.0) {
+      Some(x) => Some(nptr(x, PhantomData)),
+      None => None,
+    };
   }
 
-  /// Allocates memory for a slice of `count` `T`s with the global allocator.
-  ///
-  /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
-  /// return.
+  /// Converts a `ptr<T>` without checking its address.
   ///
   /// # SAFETY
   ///
-  /// - `T` must have non-zero size,
-  /// - `count` must be non-zero, and
-  /// - `count` `T`s must not overflow `Layout`.
-
-  pub unsafe fn alloc_slice<T>(count: usize) -> ptr<T> {
-    debug_assert!(size_of::<T>() != 0);
-    debug_assert!(count != 0);
-    debug_assert!(count <= isize::MAX as usize / size_of::<T>());
+  /// The pointer must not have address zero.
 
-    let align = align_of::<T>();
-    let size = count * size_of::<T>();
-    let layout = unsafe { Layout::from_size_align_unchecked(size, align) };
-    return unsafe { alloc_layout(layout) };
+  #[inline(always)]
+  pub const unsafe fn new_unchecked(x: ptr<T>) -> nptr<T> {
+    return nptr(unsafe { NonNull::new_unchecked(x.0) }, PhantomData);
   }
 
-  /// Allocates zero-initialized memory for a slice of `count` `T`s with the
-  /// global allocator.
+  #[inline(always)]
+  fn wrap(x: ptr<T>) -> nptr<T> {
+    match nptr::new(x) {
+      Some(x) => return x,
+      None => panic_null(),
+    }
+  }
+
+  /// Creates a pointer with the address `align_of::<T>()` and no provenance.
+
+  #[inline(always)]
+  pub const fn dangling() -> nptr<T> {
+    return unsafe { nptr::new_unchecked(ptr::invalid(align_of::<T>())) };
+  }
+
+  /// Converts into a `ptr<T>`.
+
+  #[inline(always)]
+  pub const fn as_ptr(self) -> ptr<T> {
+    return ptr(self.0.as_ptr(), PhantomData);
+  }
+
+  /// Casts the pointer to a different type.
+
+  #[inline(always)]
+  pub const fn cast<U>(self) -> nptr<U> {
+    return nptr(self.0, PhantomData);
+  }
+
+  /// The address of the pointer.
+
+  #[inline(always)]
+  pub fn addr(self) -> usize {
+    return self.as_ptr().addr();
+  }
+
+  /// Changes the address of the pointer while keeping the provenance.
+
+  #[inline(always)]
+  pub fn with_addr(self, addr: core::num::NonZero<usize>) -> nptr<T> {
+    return nptr(self.0.with_addr(addr), PhantomData);
+  }
+
+  /// Whether the pointer is aligned appropriately for `T`.
+
+  #[inline(always)]
+  pub fn is_aligned(self) -> bool {
+    return self.as_ptr().is_aligned();
+  }
+
+  /// Adds `n` bytes to the address, wrapping around, and casts.
+
+  #[inline(always)]
+  pub fn byte_add<U>(self, n: usize) -> nptr<U> {
+    return nptr::wrap(self.as_ptr().byte_add(n));
+  }
+
+  /// Subtracts `n` bytes from the address, wrapping around, and casts.
+
+  #[inline(always)]
+  pub fn byte_sub<U>(self, n: usize) -> nptr<U> {
+    return nptr::wrap(self.as_ptr().byte_sub(n));
+  }
+
+  /// The difference in bytes between the addresses, wrapping around.
+
+  #[inline(always)]
+  pub fn byte_diff<U>(self, from: nptr<U>) -> usize {
+    return self.addr().wrapping_sub(from.addr());
+  }
+
+  /// Converts into a `*const T`.
+
+  #[inline(always)]
+  pub const fn as_const_ptr(self) -> *const T {
+    return self.as_ptr().as_const_ptr();
+  }
+
+  /// Converts into a `*mut T`.
+
+  #[inline(always)]
+  pub const fn as_mut_ptr(self) -> *mut T {
+    return self.as_ptr().as_mut_ptr();
+  }
+
+  /// Converts into a `*const [T]`.
+
+  #[inline(always)]
+  pub const fn as_slice_const_ptr(self, len: usize) -> *const [T] {
+    return self.as_ptr().as_slice_const_ptr(len);
+  }
+
+  /// Converts into a `*mut [T]`.
+
+  #[inline(always)]
+  pub const fn as_slice_mut_ptr(self, len: usize) -> *mut [T] {
+    return self.as_ptr().as_slice_mut_ptr(len);
+  }
+
+  /// Converts into a `&T`.
   ///
-  /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
-  /// return.
+  /// # SAFETY
+  ///
+  /// The reference must be be valid for the lifetime.
+
+  #[inline(always)]
+  pub const unsafe fn as_ref<'a>(self) -> &'a T {
+    return unsafe { self.as_ptr().as_ref() };
+  }
+
+  /// Converts into a `&mut T`.
   ///
   /// # SAFETY
   ///
-  /// - `T` must have non-zero size,
-  /// - `count` must be non-zero, and
-  /// - `count` `T`s must not overflow `Layout`.
+  /// The reference must be be valid for the lifetime.
 
-  pub unsafe fn alloc_slice_zeroed<T>(count: usize) -> ptr<T> {
-    debug_assert!(size_of::<T>() != 0);
-    debug_assert!(count != 0);
-    debug_assert!(count <= isize::MAX as usize / size_of::<T>());
+  #[inline(always)]
+  pub const unsafe fn as_mut_ref<'a>(self) -> &'a mut T {
+    return unsafe { self.as_ptr().as_mut_ref() };
+  }
 
-    let align = align_of::<T>();
-    let size = count * size_of::<T>();
-    let layout = unsafe { Layout::from_size_align_unchecked(size, align) };
-    return unsafe { alloc_layout_zeroed(layout) };
+  /// Converts into a `&[T]`.
+  ///
+  /// # SAFETY
+  ///
+  /// The reference must be be valid for the lifetime.
+
+  #[inline(always)]
+  pub const unsafe fn as_slice_ref<'a>(self, len: usize) -> &'a [T] {
+    return unsafe { self.as_ptr().as_slice_ref(len) };
   }
 
-  /// Deallocates memory with the global allocator.
+  /// Converts into a `&mut [T]`.
   ///
   /// # SAFETY
   ///
-  /// See [alloc::alloc::GlobalAlloc::dealloc].
+  /// The reference must be be valid for the lifetime.
 
-  pub unsafe fn dealloc_layout<T>(x: ptr<T>, layout: Layout) {
-    unsafe { alloc::alloc::dealloc(x.cast().as_mut_ptr(), layout) };
+  #[inline(always)]
+  pub const unsafe fn as_slice_mut_ref<'a>(self, len: usize) -> &'a mut [T] {
+    return unsafe { self.as_ptr().as_slice_mut_ref(len) };
   }
 
-  /// Deallocates memory for a `T` with the global allocator.
+  /// Converts into a `NonNull<T>`.
+
+  #[inline(always)]
+  pub const fn as_non_null(self) -> NonNull<T> {
+    return self.0.cast();
+  }
+
+  /// Converts into a `NonNull<[T]>`.
+
+  #[inline(always)]
+  pub const fn as_slice_non_null(self, len: usize) -> NonNull<[T]> {
+    return NonNull::slice_from_raw_parts(self.0.cast(), len);
+  }
+
+  /// Reads a value.
   ///
   /// # SAFETY
   ///
-  /// `x` must be currently allocated for a `T`.
+  /// See [core::ptr::read].
 
-  pub unsafe fn dealloc<T>(x: ptr<T>) {
-    unsafe { dealloc_layout(x, Layout::new::<T>()) };
+  #[inline(always)]
+  pub const unsafe fn read(self) -> T {
+    return unsafe { self.as_ptr().read() };
   }
 
-  /// Deallocates memory for `count` `T`s with the global allocator.
+  /// Reads a value without requiring alignment.
   ///
   /// # SAFETY
   ///
-  /// `x` must be currently allocated for a slice of `count` `T`s.
+  /// See [core::ptr::read_unaligned].
 
-  pub unsafe fn dealloc_slice<T>(x: ptr<T>, count: usize) {
-    let align = align_of::<T>();
-    let size = count * size_of::<T>();
-    let layout = unsafe { Layout::from_size_align_unchecked(size, align) };
-    unsafe { dealloc_layout(x, layout) };
+  #[inline(always)]
+  pub const unsafe fn read_unaligned(self) -> T {
+    return unsafe { self.as_ptr().read_unaligned() };
   }
 
-  /// Reallocates memory with the global allocator.
+  /// # SAFETY
   ///
-  /// On failure, does not alter the old block, calls
-  /// [`alloc::alloc::handle_alloc_error`], and does not return.
+  /// See [core::ptr::read_volatile].
+
+  #[inline(always)]
+  pub unsafe fn read_volatile(self) -> T {
+    return unsafe { self.as_ptr().read_volatile() };
+  }
+
+  /// Writes a value.
   ///
   /// # SAFETY
   ///
-  /// See [alloc::alloc::GlobalAlloc::realloc].
+  /// See [core::ptr::write].
 
-  pub unsafe fn realloc_layout<T>(x: ptr<T>, layout: Layout, new_size: usize) -> ptr<T> {
-    let x = unsafe { alloc::alloc::realloc(x.cast().as_mut_ptr(), layout, new_size) };
-    let x = ptr::from(x).cast();
+  #[inline(always)]
+  pub const unsafe fn write(self, value: T) {
+    unsafe { self.as_ptr().write(value) };
+  }
 
-    if x.is_null() {
-      match alloc::alloc::handle_alloc_error(layout) {
-      }
-    }
+  /// Writes a value without requiring alignment.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::write_unaligned].
 
-    return x;
+  #[inline(always)]
+  pub const unsafe fn write_unaligned(self, value: T) {
+    unsafe { self.as_ptr().write_unaligned(value) };
   }
 
-  /// Reallocates memory for a slice with the global allocator.
+  /// # SAFETY
   ///
-  /// On failure, does not alter the old block, calls
-  /// [`alloc::alloc::handle_alloc_error`], and does not return.
+  /// See [core::ptr::write_volatile].
+
+  #[inline(always)]
+  pub unsafe fn write_volatile(self, value: T) {
+    unsafe { self.as_ptr().write_volatile(value) };
+  }
+
+  /// Replaces the value at `self` with `value`, returning the old value.
   ///
   /// # SAFETY
   ///
-  /// - `x` must be currently allocated for a slice of `old_count` `T`s,
-  /// - `new_count` must be non-zero, and
-  /// - `new_count` `T`s must not overflow `Layout`.
+  /// See [core::ptr::replace].
 
-  pub unsafe fn realloc_slice<T>(x: ptr<T>, old_count: usize, new_count: usize) -> ptr<T> {
-    debug_assert!(new_count != 0);
-    debug_assert!(new_count <= isize::MAX as usize / size_of::<T>());
+  #[inline(always)]
+  pub const unsafe fn replace(self, value: T) -> T {
+    return unsafe { self.as_ptr().replace(value) };
+  }
 
-    let align = align_of::<T>();
-    let old_size = old_count * size_of::<T>();
-    let new_size = new_count * size_of::<T>();
-    let layout = unsafe { Layout::from_size_align_unchecked(old_size, align) };
-    return unsafe { realloc_layout(x, layout, new_size) };
+  /// Drops the pointed-to value.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::drop_in_place].
+
+  #[inline(always)]
+  pub unsafe fn drop_in_place(self) {
+    unsafe { self.as_ptr().drop_in_place() };
+  }
+
+  /// Copies `count * size_of::<T>()` bytes from `src` to `self`. The source
+  /// and destination regions must not overlap.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::copy_nonoverlapping].
+
+  #[inline(always)]
+  pub const unsafe fn copy_from_nonoverlapping(self, src: nptr<T>, count: usize) {
+    unsafe { self.as_ptr().copy_from_nonoverlapping(src.as_ptr(), count) };
+  }
+
+  /// Swaps `count * size_of::<T>()` bytes between the regions pointed-to by
+  /// `self` and `with`.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::swap_nonoverlapping].
+
+  #[inline(always)]
+  pub const unsafe fn swap_nonoverlapping(self, with: nptr<T>, count: usize) {
+    unsafe { self.as_ptr().swap_nonoverlapping(with.as_ptr(), count) };
+  }
+
+  /// Writes `count * size_of::<T>()` copies of byte `value` at `x`.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::write_bytes].
+
+  #[inline(always)]
+  pub const unsafe fn write_bytes(self, value: u8, count: usize) {
+    unsafe { self.as_ptr().write_bytes(value, count) };
+  }
+
+  /// Copies `count * size_of::<T>()` bytes from `src` to `self`. The source
+  /// and destination regions may overlap.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::copy].
+
+  #[inline(always)]
+  pub const unsafe fn copy_from(self, src: nptr<T>, count: usize) {
+    unsafe { self.as_ptr().copy_from(src.as_ptr(), count) };
+  }
+
+  /// Copies `count * size_of::<T>()` bytes from `self` to `dst`. The source
+  /// and destination regions may overlap.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::copy].
+
+  #[inline(always)]
+  pub const unsafe fn copy_to(self, dst: nptr<T>, count: usize) {
+    unsafe { self.as_ptr().copy_to(dst.as_ptr(), count) };
+  }
+
+  /// Copies `count * size_of::<T>()` bytes from `self` to `dst`. The source
+  /// and destination regions must not overlap.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::copy_nonoverlapping].
+
+  #[inline(always)]
+  pub const unsafe fn copy_to_nonoverlapping(self, dst: nptr<T>, count: usize) {
+    unsafe { self.as_ptr().copy_to_nonoverlapping(dst.as_ptr(), count) };
+  }
+
+  /// Swaps the values at `self` and `with`, which may be equal.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::swap].
+
+  #[inline(always)]
+  pub const unsafe fn swap(self, with: nptr<T>) {
+    unsafe { self.as_ptr().swap(with.as_ptr()) };
+  }
+
+  /// Writes `count` clones of `value` at `self`. See [`ptr::fill`].
+  ///
+  /// # SAFETY
+  ///
+  /// The region must be valid for writes of `count` values of `T` and
+  /// properly aligned.
+
+  #[inline(always)]
+  pub unsafe fn fill(self, value: T, count: usize) where T: Clone {
+    unsafe { self.as_ptr().fill(value, count) };
+  }
+
+  /// Lexicographically compares `count * size_of::<T>()` bytes at `self`
+  /// with those at `other`.
+  ///
+  /// # SAFETY
+  ///
+  /// Both regions must be valid for reads and their bytes must be
+  /// initialized. In particular, `T` must not have padding.
+
+  #[inline(always)]
+  pub unsafe fn compare_bytes(self, other: nptr<T>, count: usize) -> core::cmp::Ordering {
+    return unsafe { self.as_ptr().compare_bytes(other.as_ptr(), count) };
+  }
+}
+
+impl<T> From<NonNull<T>> for nptr<T> {
+  #[inline(always)]
+  fn from(value: NonNull<T>) -> nptr<T> {
+    return nptr(value.cast(), PhantomData);
+  }
+}
+
+impl<T> From<&T> for nptr<T> {
+  #[inline(always)]
+  fn from(value: &T) -> nptr<T> {
+    return nptr(NonNull::from(value).cast(), PhantomData);
+  }
+}
+
+impl<T> From<&mut T> for nptr<T> {
+  #[inline(always)]
+  fn from(value: &mut T) -> nptr<T> {
+    return nptr(NonNull::from(value).cast(), PhantomData);
+  }
+}
+
+impl<T> From<NonNull<[T]>> for nptr<T> {
+  #[inline(always)]
+  fn from(value: NonNull<[T]>) -> nptr<T> {
+    return nptr(value.cast(), PhantomData);
+  }
+}
+
+impl<T> From<&[T]> for nptr<T> {
+  #[inline(always)]
+  fn from(value: &[T]) -> nptr<T> {
+    return nptr(NonNull::from(value).cast(), PhantomData);
+  }
+}
+
+impl<T> From<&mut [T]> for nptr<T> {
+  #[inline(always)]
+  fn from(value: &mut [T]) -> nptr<T> {
+    return nptr(NonNull::from(value).cast(), PhantomData);
+  }
+}
+
+impl<T> From<nptr<T>> for ptr<T> {
+  #[inline(always)]
+  fn from(value: nptr<T>) -> ptr<T> {
+    return value.as_ptr();
+  }
+}
+
+impl<T> From<nptr<T>> for NonNull<T> {
+  #[inline(always)]
+  fn from(value: nptr<T>) -> NonNull<T> {
+    return value.as_non_null();
+  }
+}
+
+impl<T> From<nptr<T>> for *const T {
+  #[inline(always)]
+  fn from(value: nptr<T>) -> *const T {
+    return value.as_const_ptr();
+  }
+}
+
+impl<T> From<nptr<T>> for *mut T {
+  #[inline(always)]
+  fn from(value: nptr<T>) -> *mut T {
+    return value.as_mut_ptr();
+  }
+}
+
+impl<T> core::ops::Add<isize> for nptr<T> {
+  type Output = nptr<T>;
+
+  #[inline(always)]
+  fn add(self, rhs: isize) -> Self::Output {
+    return nptr::wrap(self.as_ptr() + rhs);
+  }
+}
+
+impl<T> core::ops::Add<usize> for nptr<T> {
+  type Output = nptr<T>;
+
+  #[inline(always)]
+  fn add(self, rhs: usize) -> Self::Output {
+    return nptr::wrap(self.as_ptr() + rhs);
+  }
+}
+
+impl<T> core::ops::Add<i32> for nptr<T> {
+  type Output = nptr<T>;
+
+  #[inline(always)]
+  fn add(self, rhs: i32) -> Self::Output {
+    return nptr::wrap(self.as_ptr() + rhs);
+  }
+}
+
+impl<T> core::ops::Add<u32> for nptr<T> {
+  type Output = nptr<T>;
+
+  #[inline(always)]
+  fn add(self, rhs: u32) -> Self::Output {
+    return nptr::wrap(self.as_ptr() + rhs);
+  }
+}
+
+impl<T> core::ops::Add<i64> for nptr<T> {
+  type Output = nptr<T>;
+
+  #[inline(always)]
+  fn add(self, rhs: i64) -> Self::Output {
+    return nptr::wrap(self.as_ptr() + rhs);
+  }
+}
+
+impl<T> core::ops::Add<u64> for nptr<T> {
+  type Output = nptr<T>;
+
+  #[inline(always)]
+  fn add(self, rhs: u64) -> Self::Output {
+    return nptr::wrap(self.as_ptr() + rhs);
+  }
+}
+
+impl<T> core::ops::Sub<isize> for nptr<T> {
+  type Output = nptr<T>;
+
+  #[inline(always)]
+  fn sub(self, rhs: isize) -> Self::Output {
+    return nptr::wrap(self.as_ptr() - rhs);
+  }
+}
+
+impl<T> core::ops::Sub<usize> for nptr<T> {
+  type Output = nptr<T>;
+
+  #[inline(always)]
+  fn sub(self, rhs: usize) -> Self::Output {
+    return nptr::wrap(self.as_ptr() - rhs);
+  }
+}
+
+impl<T> core::ops::Sub<i32> for nptr<T> {
+  type Output = nptr<T>;
+
+  #[inline(always)]
+  fn sub(self, rhs: i32) -> Self::Output {
+    return nptr::wrap(self.as_ptr() - rhs);
+  }
+}
+
+impl<T> core::ops::Sub<u32> for nptr<T> {
+  type Output = nptr<T>;
+
+  #[inline(always)]
+  fn sub(self, rhs: u32) -> Self::Output {
+    return nptr::wrap(self.as_ptr() - rhs);
+  }
+}
+
+impl<T> core::ops::Sub<i64> for nptr<T> {
+  type Output = nptr<T>;
+
+  #[inline(always)]
+  fn sub(self, rhs: i64) -> Self::Output {
+    return nptr::wrap(self.as_ptr() - rhs);
+  }
+}
+
+impl<T> core::ops::Sub<u64> for nptr<T> {
+  type Output = nptr<T>;
+
+  #[inline(always)]
+  fn sub(self, rhs: u64) -> Self::Output {
+    return nptr::wrap(self.as_ptr() - rhs);
+  }
+}
+
+impl<T> core::ops::Sub<nptr<T>> for nptr<T> {
+  type Output = usize;
+
+  #[inline(always)]
+  fn sub(self, rhs: nptr<T>) -> Self::Output {
+    return self.as_ptr() - rhs.as_ptr();
+  }
+}
+
+impl<T> core::ops::AddAssign<isize> for nptr<T> {
+  #[inline(always)]
+  fn add_assign(&mut self, rhs: isize) {
+    *self = *self + rhs;
+  }
+}
+
+impl<T> core::ops::AddAssign<usize> for nptr<T> {
+  #[inline(always)]
+  fn add_assign(&mut self, rhs: usize) {
+    *self = *self + rhs;
+  }
+}
+
+impl<T> core::ops::AddAssign<i32> for nptr<T> {
+  #[inline(always)]
+  fn add_assign(&mut self, rhs: i32) {
+    *self = *self + rhs;
+  }
+}
+
+impl<T> core::ops::AddAssign<u32> for nptr<T> {
+  #[inline(always)]
+  fn add_assign(&mut self, rhs: u32) {
+    *self = *self + rhs;
+  }
+}
+
+impl<T> core::ops::AddAssign<i64> for nptr<T> {
+  #[inline(always)]
+  fn add_assign(&mut self, rhs: i64) {
+    *self = *self + rhs;
+  }
+}
+
+impl<T> core::ops::AddAssign<u64> for nptr<T> {
+  #[inline(always)]
+  fn add_assign(&mut self, rhs: u64) {
+    *self = *self + rhs;
+  }
+}
+
+impl<T> core::ops::SubAssign<isize> for nptr<T> {
+  #[inline(always)]
+  fn sub_assign(&mut self, rhs: isize) {
+    *self = *self - rhs;
+  }
+}
+
+impl<T> core::ops::SubAssign<usize> for nptr<T> {
+  #[inline(always)]
+  fn sub_assign(&mut self, rhs: usize) {
+    *self = *self - rhs;
+  }
+}
+
+impl<T> core::ops::SubAssign<i32> for nptr<T> {
+  #[inline(always)]
+  fn sub_assign(&mut self, rhs: i32) {
+    *self = *self - rhs;
+  }
+}
+
+impl<T> core::ops::SubAssign<u32> for nptr<T> {
+  #[inline(always)]
+  fn sub_assign(&mut self, rhs: u32) {
+    *self = *self - rhs;
+  }
+}
+
+impl<T> core::ops::SubAssign<i64> for nptr<T> {
+  #[inline(always)]
+  fn sub_assign(&mut self, rhs: i64) {
+    *self = *self - rhs;
+  }
+}
+
+impl<T> core::ops::SubAssign<u64> for nptr<T> {
+  #[inline(always)]
+  fn sub_assign(&mut self, rhs: u64) {
+    *self = *self - rhs;
+  }
+}
+
+impl<T> core::fmt::Pointer for nptr<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    return <NonNull<u8> as core::fmt::Pointer>::fmt(&self.0, f);
+  }
+}
+
+impl<T> core::fmt::Debug for nptr<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    return <NonNull<u8> as core::fmt::Pointer>::fmt(&self.0, f);
+  }
+}
+
+/// A [`ptr`] with a small integer tag packed into its low `BITS` bits.
+///
+/// The low bits of a pointer aligned for `T` are always zero, so they can
+/// hold `BITS` bits of tag as long as `1 << BITS <= align_of::<T>()`. This is
+/// checked at compile time. The tag is changed with [`ptr::with_addr`], so
+/// provenance is kept.
+
+#[allow(non_camel_case_types)]
+#[repr(transparent)]
+pub struct tagged<T, const BITS: u32>(ptr<T>);
+
+impl<T, const BITS: u32> Clone for tagged<T, BITS> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    return *self;
+  }
+}
+
+impl<T, const BITS: u32> Copy for tagged<T, BITS> {
+}
+
+impl<T, const BITS: u32> Eq for tagged<T, BITS> {
+}
+
+impl<T, const BITS: u32> core::hash::Hash for tagged<T, BITS> {
+  #[inline(always)]
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.0.hash(state);
+  }
+}
+
+impl<T, const BITS: u32> PartialEq for tagged<T, BITS> {
+  #[inline(always)]
+  fn eq(&self, other: &Self) -> bool {
+    return self.0 == other.0;
+  }
+}
+
+impl<T, const BITS: u32> tagged<T, BITS> {
+  /// The mask covering the tag bits.
+
+  pub const MASK: usize = {
+    assert!(BITS < usize::BITS && 1 << BITS <= align_of::<T>(), "tag bits do not fit in the alignment of `T`");
+    (1 << BITS) - 1
+  };
+
+  /// A null pointer with tag zero.
+
+  pub const NULL: tagged<T, BITS> = tagged::from_raw(ptr::NULL);
+
+  /// Packs a pointer and a tag.
+  ///
+  /// The pointer's low `BITS` bits must be zero and the tag must be less
+  /// than `1 << BITS`. This is checked with `debug_assert!`.
+
+  #[inline(always)]
+  pub fn new(x: ptr<T>, tag: usize) -> tagged<T, BITS> {
+    debug_assert!(x.addr() & Self::MASK == 0);
+    debug_assert!(tag & ! Self::MASK == 0);
+
+    return tagged(x.with_addr(x.addr() | tag & Self::MASK));
+  }
+
+  /// Reinterprets a pointer whose low bits already hold a tag.
+
+  #[inline(always)]
+  pub const fn from_raw(x: ptr<T>) -> tagged<T, BITS> {
+    let _ = Self::MASK;
+
+    return tagged(x);
+  }
+
+  /// The packed pointer, including the tag bits.
+
+  #[inline(always)]
+  pub const fn as_raw(self) -> ptr<T> {
+    return self.0;
+  }
+
+  /// The pointer, with the tag bits cleared.
+
+  #[inline(always)]
+  pub fn ptr(self) -> ptr<T> {
+    return self.0.with_addr(self.0.addr() & ! Self::MASK);
+  }
+
+  /// The tag.
+
+  #[inline(always)]
+  pub fn tag(self) -> usize {
+    return self.0.addr() & Self::MASK;
+  }
+
+  /// Replaces the tag, keeping the pointer.
+
+  #[inline(always)]
+  pub fn with_tag(self, tag: usize) -> tagged<T, BITS> {
+    return tagged::new(self.ptr(), tag);
+  }
+
+  /// Replaces the pointer, keeping the tag.
+
+  #[inline(always)]
+  pub fn with_ptr(self, x: ptr<T>) -> tagged<T, BITS> {
+    return tagged::new(x, self.tag());
+  }
+}
+
+impl<T, const BITS: u32> Default for tagged<T, BITS> {
+  #[inline(always)]
+  fn default() -> tagged<T, BITS> {
+    return tagged::NULL;
+  }
+}
+
+impl<T, const BITS: u32> core::fmt::Debug for tagged<T, BITS> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    return f.debug_tuple("tagged").field(&self.ptr()).field(&self.tag()).finish();
+  }
+}
+
+/// A [`ptr`] with a 16-bit payload packed into the high bits of its address.
+///
+/// On x86_64 and aarch64, user-space virtual addresses use 48 bits and are
+/// canonical, meaning that bits 48 through 63 are copies of bit 47. Those top
+/// 16 bits can therefore hold a payload, which is removed, and the address
+/// sign-extended, by [`htagged::ptr`]. The dereferencing methods go through
+/// `ptr`, so they never see the payload.
+///
+/// Whether an address fits is checked at runtime, in [`htagged::new`]. Some
+/// systems can be configured with larger address spaces, in which case `new`
+/// fails for addresses that use more than 48 bits.
+
+#[cfg(target_pointer_width = "64")]
+#[allow(non_camel_case_types)]
+#[repr(transparent)]
+pub struct htagged<T>(ptr<T>);
+
+#[cfg(target_pointer_width = "64")]
+impl<T> Clone for htagged<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    return *self;
+  }
+}
+
+#[cfg(target_pointer_width = "64")]
+impl<T> Copy for htagged<T> {
+}
+
+#[cfg(target_pointer_width = "64")]
+impl<T> Eq for htagged<T> {
+}
+
+#[cfg(target_pointer_width = "64")]
+impl<T> core::hash::Hash for htagged<T> {
+  #[inline(always)]
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.0.hash(state);
+  }
+}
+
+#[cfg(target_pointer_width = "64")]
+impl<T> PartialEq for htagged<T> {
+  #[inline(always)]
+  fn eq(&self, other: &Self) -> bool {
+    return self.0 == other.0;
+  }
+}
+
+#[cfg(target_pointer_width = "64")]
+impl<T> htagged<T> {
+  /// The number of address bits.
+
+  pub const ADDR_BITS: u32 = 48;
+
+  /// A null pointer with payload zero.
+
+  pub const NULL: htagged<T> = htagged(ptr::NULL);
+
+  /// Whether an address is canonical, that is, whether its top 16 bits are
+  /// copies of bit 47.
+
+  #[inline(always)]
+  pub const fn is_canonical(addr: usize) -> bool {
+    return Self::canonicalize(addr) == addr;
+  }
+
+  #[inline(always)]
+  const fn canonicalize(addr: usize) -> usize {
+    let n = usize::BITS - Self::ADDR_BITS;
+    return ((addr << n) as isize >> n) as usize;
+  }
+
+  /// Packs a pointer and a payload. Returns `None` if the pointer's address
+  /// is not canonical.
+
+  #[inline(always)]
+  pub fn new(x: ptr<T>, payload: u16) -> Option<htagged<T>> {
+    if ! Self::is_canonical(x.addr()) {
+      return None;
+    }
+
+    return Some(Self::pack(x, payload));
+  }
+
+  #[inline(always)]
+  fn pack(x: ptr<T>, payload: u16) -> htagged<T> {
+    let mask = (1 << Self::ADDR_BITS) - 1;
+    return htagged(x.with_addr(x.addr() & mask | (payload as usize) << Self::ADDR_BITS));
+  }
+
+  /// Reinterprets a pointer whose high bits already hold a payload.
+
+  #[inline(always)]
+  pub const fn from_raw(x: ptr<T>) -> htagged<T> {
+    return htagged(x);
+  }
+
+  /// The packed pointer, including the payload. It must not be dereferenced.
+
+  #[inline(always)]
+  pub const fn as_raw(self) -> ptr<T> {
+    return self.0;
+  }
+
+  /// The pointer, with the payload removed and the address sign-extended.
+
+  #[inline(always)]
+  pub fn ptr(self) -> ptr<T> {
+    return self.0.with_addr(Self::canonicalize(self.0.addr()));
+  }
+
+  /// The payload.
+
+  #[inline(always)]
+  pub fn payload(self) -> u16 {
+    return (self.0.addr() >> Self::ADDR_BITS) as u16;
+  }
+
+  /// Replaces the payload, keeping the pointer.
+
+  #[inline(always)]
+  pub fn with_payload(self, payload: u16) -> htagged<T> {
+    return Self::pack(self.0, payload);
+  }
+
+  /// Replaces the pointer, keeping the payload. Returns `None` if the
+  /// pointer's address is not canonical.
+
+  #[inline(always)]
+  pub fn with_ptr(self, x: ptr<T>) -> Option<htagged<T>> {
+    return htagged::new(x, self.payload());
+  }
+
+  /// Converts into a `&T`.
+  ///
+  /// # SAFETY
+  ///
+  /// The reference must be be valid for the lifetime.
+
+  #[inline(always)]
+  pub unsafe fn as_ref<'a>(self) -> &'a T {
+    return unsafe { self.ptr().as_ref() };
+  }
+
+  /// Converts into a `&mut T`.
+  ///
+  /// # SAFETY
+  ///
+  /// The reference must be be valid for the lifetime.
+
+  #[inline(always)]
+  pub unsafe fn as_mut_ref<'a>(self) -> &'a mut T {
+    return unsafe { self.ptr().as_mut_ref() };
+  }
+
+  /// Reads a value.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::read].
+
+  #[inline(always)]
+  pub unsafe fn read(self) -> T {
+    return unsafe { self.ptr().read() };
+  }
+
+  /// Writes a value.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::write].
+
+  #[inline(always)]
+  pub unsafe fn write(self, value: T) {
+    unsafe { self.ptr().write(value) };
+  }
+}
+
+#[cfg(target_pointer_width = "64")]
+impl<T> Default for htagged<T> {
+  #[inline(always)]
+  fn default() -> htagged<T> {
+    return htagged::NULL;
+  }
+}
+
+#[cfg(target_pointer_width = "64")]
+impl<T> core::fmt::Debug for htagged<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    return f.debug_tuple("htagged").field(&self.ptr()).field(&self.payload()).finish();
+  }
+}
+
+/// A type whose pointers can be split into a data pointer and metadata.
+///
+/// This is implemented for sized types, whose metadata is `()`, for slices
+/// and `str`, whose metadata is the length, and for a few `dyn Any` types.
+/// Other trait objects can opt in with [`dyn_pointee!`].
+///
+/// # SAFETY
+///
+/// `from_raw_parts(metadata(x).0, metadata(x).1)` must return a pointer equal
+/// to `x`, with the provenance of the data pointer.
+
+pub unsafe trait Pointee {
+  /// The metadata.
+
+  type Metadata: Copy + Eq + core::hash::Hash + core::fmt::Debug;
+
+  /// Splits off the metadata.
+
+  fn metadata(x: *const Self) -> Self::Metadata;
+
+  /// Joins a data pointer and metadata.
+
+  fn from_raw_parts(data: ptr<()>, metadata: Self::Metadata) -> *mut Self;
+}
+
+unsafe impl<T> Pointee for T {
+  type Metadata = ();
+
+  #[inline(always)]
+  fn metadata(_: *const T) {
+  }
+
+  #[inline(always)]
+  fn from_raw_parts(data: ptr<()>, (): ()) -> *mut T {
+    return data.cast().as_mut_ptr();
+  }
+}
+
+unsafe impl<T> Pointee for [T] {
+  type Metadata = usize;
+
+  #[inline(always)]
+  fn metadata(x: *const [T]) -> usize {
+    return x.len();
+  }
+
+  #[inline(always)]
+  fn from_raw_parts(data: ptr<()>, len: usize) -> *mut [T] {
+    return data.cast().as_slice_mut_ptr(len);
+  }
+}
+
+unsafe impl Pointee for str {
+  type Metadata = usize;
+
+  #[inline(always)]
+  fn metadata(x: *const str) -> usize {
+    return (x as *const [u8]).len();
+  }
+
+  #[inline(always)]
+  fn from_raw_parts(data: ptr<()>, len: usize) -> *mut str {
+    return data.cast::<u8>().as_slice_mut_ptr(len) as *mut str;
+  }
+}
+
+/// The metadata of a trait object, which is a pointer to its vtable.
+///
+/// This relies on trait object pointers being laid out as a data pointer
+/// followed by a vtable pointer, which the language does not guarantee. Both
+/// are checked at compile time: the size for each `T`, and the field order by
+/// a round trip of a `&dyn Any` through that layout.
+
+pub struct DynMetadata<T: ?Sized>(*const (), PhantomData<fn(T) -> T>);
+
+unsafe impl<T: ?Sized> Send for DynMetadata<T> {
+}
+
+unsafe impl<T: ?Sized> Sync for DynMetadata<T> {
+}
+
+impl<T: ?Sized> Clone for DynMetadata<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    return *self;
+  }
+}
+
+impl<T: ?Sized> Copy for DynMetadata<T> {
+}
+
+impl<T: ?Sized> Eq for DynMetadata<T> {
+}
+
+impl<T: ?Sized> PartialEq for DynMetadata<T> {
+  #[inline(always)]
+  fn eq(&self, other: &Self) -> bool {
+    return self.0 == other.0;
+  }
+}
+
+impl<T: ?Sized> core::hash::Hash for DynMetadata<T> {
+  #[inline(always)]
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.0.hash(state);
+  }
+}
+
+impl<T: ?Sized> core::fmt::Debug for DynMetadata<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    return f.debug_tuple("DynMetadata").field(&self.0).finish();
+  }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct DynParts {
+  data: *mut (),
+  vtable: *const (),
+}
+
+#[repr(C)]
+union DynRepr<T: ?Sized> {
+  fat: *mut T,
+  parts: DynParts,
+}
+
+const DYN_LAYOUT: () = {
+  let x: &dyn core::any::Any = &0x5au8;
+  let parts = unsafe { DynRepr::<dyn core::any::Any> { fat: x as *const dyn core::any::Any as *mut _ }.parts };
+  assert!(unsafe { *(parts.data as *const u8) } == 0x5a, "unexpected trait object layout");
+  let y = unsafe { DynRepr::<dyn core::any::Any> { parts }.fat };
+  assert!(unsafe { *(y as *const u8) } == 0x5a, "unexpected trait object layout");
+};
+
+impl<T: ?Sized> DynMetadata<T> {
+  const CHECK: () = {
+    let () = DYN_LAYOUT;
+    assert!(size_of::<*mut T>() == size_of::<DynParts>(), "not a trait object");
+  };
+
+  /// The vtable pointer.
+
+  #[inline(always)]
+  pub fn vtable(self) -> *const () {
+    return self.0;
+  }
+
+  /// Splits off the metadata of a trait object pointer. Used by
+  /// [`dyn_pointee!`].
+  ///
+  /// # SAFETY
+  ///
+  /// `T` must be a trait object type.
+
+  #[doc(hidden)]
+  #[inline(always)]
+  pub unsafe fn metadata(x: *const T) -> DynMetadata<T> {
+    let () = Self::CHECK;
+
+    return DynMetadata(unsafe { DynRepr::<T> { fat: x as *mut T }.parts }.vtable, PhantomData);
+  }
+
+  /// Joins a data pointer and metadata into a trait object pointer. Used by
+  /// [`dyn_pointee!`].
+  ///
+  /// # SAFETY
+  ///
+  /// `T` must be a trait object type, and `metadata` must have been split off
+  /// a pointer to `T`.
+
+  #[doc(hidden)]
+  #[inline(always)]
+  pub unsafe fn from_raw_parts(data: ptr<()>, metadata: DynMetadata<T>) -> *mut T {
+    let () = Self::CHECK;
+
+    return unsafe { DynRepr::<T> { parts: DynParts { data: data.cast().as_mut_ptr(), vtable: metadata.0 } }.fat };
+  }
+}
+
+/// Implements [`Pointee`] for trait object types, with [`DynMetadata`] as the
+/// metadata.
+///
+/// Each type is written after `unsafe`, which stands in for the `unsafe impl`
+/// that the macro expands to.
+///
+/// # SAFETY
+///
+/// Each type must be a trait object type. The macro checks at compile time
+/// only that a pointer to it is two words wide.
+///
+/// ```
+/// trait Shape {}
+///
+/// pop::dyn_pointee!(unsafe dyn Shape, unsafe dyn Shape + Send);
+/// ```
+///
+/// ```compile_fail
+/// trait Shape {}
+///
+/// pop::dyn_pointee!(dyn Shape);
+/// ```
+
+#[macro_export]
+macro_rules! dyn_pointee {
+  ($(unsafe $t:ty),* $(,)?) => {
+    $(
+      unsafe impl $crate::Pointee for $t {
+        type Metadata = $crate::DynMetadata<$t>;
+
+        #[allow(clippy::not_unsafe_ptr_arg_deref)]
+        #[inline(always)]
+        fn metadata(x: *const $t) -> $crate::DynMetadata<$t> {
+          return unsafe { $crate::DynMetadata::metadata(x) };
+        }
+
+        #[inline(always)]
+        fn from_raw_parts(data: $crate::ptr<()>, metadata: $crate::DynMetadata<$t>) -> *mut $t {
+          return unsafe { $crate::DynMetadata::from_raw_parts(data, metadata) };
+        }
+      }
+    )*
+  };
+}
+
+dyn_pointee!(
+  unsafe dyn core::any::Any,
+  unsafe dyn core::any::Any + Send,
+  unsafe dyn core::any::Any + Send + Sync,
+);
+
+/// A pointer to a possibly unsized `T`, carrying its metadata.
+///
+/// Where [`ptr`] is a thin address, an `fptr` is a full raw pointer, so it
+/// round-trips `*mut [T]` and `*mut dyn Trait` without losing the length or
+/// the vtable. [`fptr::cast`] drops the metadata and gives back a `ptr`.
+
+#[allow(non_camel_case_types)]
+#[repr(transparent)]
+pub struct fptr<T: ?Sized>(*mut T);
+
+unsafe impl<T: ?Sized> Send for fptr<T> {
+}
+
+unsafe impl<T: ?Sized> Sync for fptr<T> {
+}
+
+impl<T: ?Sized> Clone for fptr<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    return *self;
+  }
+}
+
+impl<T: ?Sized> Copy for fptr<T> {
+}
+
+impl<T: ?Sized> Eq for fptr<T> {
+}
+
+impl<T: ?Sized> PartialEq for fptr<T> {
+  #[inline(always)]
+  fn eq(&self, other: &Self) -> bool {
+    return core::ptr::eq(self.0, other.0);
+  }
+}
+
+impl<T: ?Sized> core::hash::Hash for fptr<T> {
+  #[inline(always)]
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.0.hash(state);
+  }
+}
+
+impl<T: ?Sized> fptr<T> {
+  /// Joins a data pointer and metadata.
+
+  #[inline(always)]
+  pub fn from_raw_parts(data: ptr<()>, metadata: T::Metadata) -> fptr<T>
+  where
+    T: Pointee
+  {
+    return fptr(T::from_raw_parts(data, metadata));
+  }
+
+  /// Splits into a data pointer and metadata.
+
+  #[inline(always)]
+  pub fn to_raw_parts(self) -> (ptr<()>, T::Metadata)
+  where
+    T: Pointee
+  {
+    return (self.cast(), self.metadata());
+  }
+
+  /// The metadata.
+
+  #[inline(always)]
+  pub fn metadata(self) -> T::Metadata
+  where
+    T: Pointee
+  {
+    return T::metadata(self.0);
+  }
+
+  /// Drops the metadata and casts the data pointer to a sized type.
+
+  #[inline(always)]
+  pub fn cast<U>(self) -> ptr<U> {
+    return ptr(self.0 as *mut u8, PhantomData);
+  }
+
+  /// The address of the data pointer.
+
+  #[inline(always)]
+  pub fn addr(self) -> usize {
+    return self.0.addr();
+  }
+
+  /// Whether the data pointer's address is zero.
+
+  #[inline(always)]
+  pub fn is_null(self) -> bool {
+    return self.addr() == 0;
+  }
+
+  /// Changes the address of the data pointer while keeping the provenance and
+  /// the metadata.
+
+  #[inline(always)]
+  pub fn with_addr(self, addr: usize) -> fptr<T> {
+    return fptr(self.0.with_addr(addr));
+  }
+
+  /// Converts into a `*const T`.
+
+  #[inline(always)]
+  pub const fn as_const_ptr(self) -> *const T {
+    return self.0;
+  }
+
+  /// Converts into a `*mut T`.
+
+  #[inline(always)]
+  pub const fn as_mut_ptr(self) -> *mut T {
+    return self.0;
+  }
+
+  /// Converts into a `&T`.
+  ///
+  /// # SAFETY
+  ///
+  /// The reference must be be valid for the lifetime.
+
+  #[inline(always)]
+  pub const unsafe fn as_ref<'a>(self) -> &'a T {
+    return unsafe { &*self.0 };
+  }
+
+  /// Converts into a `&mut T`.
+  ///
+  /// # SAFETY
+  ///
+  /// The reference must be be valid for the lifetime.
+
+  #[inline(always)]
+  pub const unsafe fn as_mut_ref<'a>(self) -> &'a mut T {
+    return unsafe { &mut *self.0 };
+  }
+
+  /// Converts into a `NonNull<T>`.
+  ///
+  /// # SAFETY
+  ///
+  /// The pointer must not have address zero.
+
+  #[inline(always)]
+  pub const unsafe fn as_non_null(self) -> NonNull<T> {
+    return unsafe { NonNull::new_unchecked(self.0) };
+  }
+
+  /// Drops the pointed-to value.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::drop_in_place].
+
+  #[inline(always)]
+  pub unsafe fn drop_in_place(self) {
+    unsafe { core::ptr::drop_in_place(self.0) };
+  }
+}
+
+impl<T> fptr<[T]> {
+  /// Creates a slice pointer.
+
+  #[inline(always)]
+  pub const fn from_slice_parts(data: ptr<T>, len: usize) -> fptr<[T]> {
+    return fptr(data.as_slice_mut_ptr(len));
+  }
+
+  /// The length of the slice.
+
+  #[inline(always)]
+  pub const fn len(self) -> usize {
+    return self.0.len();
+  }
+
+  /// Whether the slice is empty.
+
+  #[inline(always)]
+  pub const fn is_empty(self) -> bool {
+    return self.len() == 0;
+  }
+
+  /// The pointer to the first element.
+
+  #[inline(always)]
+  pub const fn as_ptr(self) -> ptr<T> {
+    return ptr(self.0 as *mut u8, PhantomData);
+  }
+}
+
+impl<T: ?Sized> From<*const T> for fptr<T> {
+  #[inline(always)]
+  fn from(value: *const T) -> fptr<T> {
+    return fptr(value as *mut T);
+  }
+}
+
+impl<T: ?Sized> From<*mut T> for fptr<T> {
+  #[inline(always)]
+  fn from(value: *mut T) -> fptr<T> {
+    return fptr(value);
+  }
+}
+
+impl<T: ?Sized> From<&T> for fptr<T> {
+  #[inline(always)]
+  fn from(value: &T) -> fptr<T> {
+    return fptr(value as *const T as *mut T);
+  }
+}
+
+impl<T: ?Sized> From<&mut T> for fptr<T> {
+  #[inline(always)]
+  fn from(value: &mut T) -> fptr<T> {
+    return fptr(value as *mut T);
+  }
+}
+
+impl<T: ?Sized> From<NonNull<T>> for fptr<T> {
+  #[inline(always)]
+  fn from(value: NonNull<T>) -> fptr<T> {
+    return fptr(value.as_ptr());
+  }
+}
+
+impl<T> From<ptr<T>> for fptr<T> {
+  #[inline(always)]
+  fn from(value: ptr<T>) -> fptr<T> {
+    return fptr(value.as_mut_ptr());
+  }
+}
+
+impl<T: ?Sized> From<fptr<T>> for *const T {
+  #[inline(always)]
+  fn from(value: fptr<T>) -> *const T {
+    return value.0;
+  }
+}
+
+impl<T: ?Sized> From<fptr<T>> for *mut T {
+  #[inline(always)]
+  fn from(value: fptr<T>) -> *mut T {
+    return value.0;
+  }
+}
+
+impl<T: ?Sized> core::fmt::Pointer for fptr<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    return <*mut T as core::fmt::Pointer>::fmt(&self.0, f);
+  }
+}
+
+impl<T: ?Sized> core::fmt::Debug for fptr<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    return <*mut T as core::fmt::Pointer>::fmt(&self.0, f);
+  }
+}
+
+/// A half-open range `[begin, end)` of `ptr<T>`.
+///
+/// For zero-sized `T`, every element is at `begin`, and the length is kept
+/// as the byte distance from `begin` to `end`. This keeps `begin` aligned
+/// while still allowing the range to be split and iterated. The distance is
+/// computed with wrapping arithmetic, so for a long enough range `end` is
+/// below `begin`.
+
+#[allow(non_camel_case_types)]
+pub struct range<T> {
+  begin: ptr<T>,
+  end: ptr<T>,
+}
+
+impl<T> Clone for range<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    return *self;
+  }
+}
+
+impl<T> Copy for range<T> {
+}
+
+impl<T> Eq for range<T> {
+}
+
+impl<T> PartialEq for range<T> {
+  #[inline(always)]
+  fn eq(&self, other: &Self) -> bool {
+    return self.begin == other.begin && self.end == other.end;
+  }
+}
+
+impl<T> core::hash::Hash for range<T> {
+  #[inline(always)]
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.begin.hash(state);
+    self.end.hash(state);
+  }
+}
+
+impl<T> range<T> {
+  const IS_ZST: bool = size_of::<T>() == 0;
+
+  /// The empty range at a null pointer.
+
+  pub const EMPTY: range<T> = range { begin: ptr::NULL, end: ptr::NULL };
+
+  /// Creates a range from its ends.
+  ///
+  /// For zero-sized `T`, `end` must be `begin` offset by the length in
+  /// bytes, wrapping around the address space if need be. Prefer
+  /// [`range::from_len`] in generic code.
+
+  #[inline(always)]
+  pub fn new(begin: ptr<T>, end: ptr<T>) -> range<T> {
+    debug_assert!(Self::IS_ZST || begin <= end);
+
+    return range { begin, end };
+  }
+
+  /// Creates a range of `len` elements starting at `begin`.
+
+  #[inline(always)]
+  pub fn from_len(begin: ptr<T>, len: usize) -> range<T> {
+    let end = if Self::IS_ZST { begin.byte_add(len) } else { begin + len };
+    return range::new(begin, end);
+  }
+
+  /// The first element.
+
+  #[inline(always)]
+  pub fn begin(self) -> ptr<T> {
+    return self.begin;
+  }
+
+  /// One past the last element.
+  ///
+  /// For zero-sized `T`, this is `begin` offset by the length in bytes.
+
+  #[inline(always)]
+  pub fn end(self) -> ptr<T> {
+    return self.end;
+  }
+
+  /// The number of elements.
+
+  #[inline(always)]
+  pub fn len(self) -> usize {
+    return if Self::IS_ZST { self.end.byte_diff(self.begin) } else { self.end - self.begin };
+  }
+
+  /// Whether the range has no elements.
+
+  #[inline(always)]
+  pub fn is_empty(self) -> bool {
+    return self.begin == self.end;
+  }
+
+  /// A pointer to the element at `index`, if it is in the range.
+
+  #[inline(always)]
+  pub fn get(self, index: usize) -> Option<ptr<T>> {
+    return if index < self.len() { Some(self.begin + index) } else { None };
+  }
+
+  /// Whether `x` points into the range.
+  ///
+  /// This compares addresses only. It does not check that `x` lies on an
+  /// element boundary.
+
+  #[inline(always)]
+  pub fn contains(self, x: ptr<T>) -> bool {
+    if Self::IS_ZST {
+      return ! self.is_empty() && x == self.begin;
+    }
+
+    return self.begin <= x && x < self.end;
+  }
+
+  /// Splits the range into `[0, mid)` and `[mid, len)`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `mid > len`.
+
+  #[inline(always)]
+  pub fn split_at(self, mid: usize) -> (range<T>, range<T>) {
+    let len = self.len();
+
+    assert!(mid <= len, "split index out of bounds");
+
+    if Self::IS_ZST {
+      return (range::from_len(self.begin, mid), range::from_len(self.begin, len - mid));
+    }
+
+    let x = self.begin + mid;
+    return (range::new(self.begin, x), range::new(x, self.end));
+  }
+
+  /// An iterator over consecutive subranges of `n` elements. The last
+  /// subrange may be shorter.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `n` is zero.
+
+  #[inline(always)]
+  pub fn chunks(self, n: usize) -> RangeChunks<T> {
+    assert!(n != 0, "chunk size must be non-zero");
+
+    return RangeChunks { rest: self, n };
+  }
+
+  /// An iterator over pointers to the elements. Use `.rev()` to iterate
+  /// backwards.
+
+  #[inline(always)]
+  pub fn iter(self) -> RangeIter<T> {
+    return RangeIter { rest: self };
+  }
+
+  /// Converts into a `*const [T]`.
+
+  #[inline(always)]
+  pub fn as_slice_const_ptr(self) -> *const [T] {
+    return self.begin.as_slice_const_ptr(self.len());
+  }
+
+  /// Converts into a `*mut [T]`.
+
+  #[inline(always)]
+  pub fn as_slice_mut_ptr(self) -> *mut [T] {
+    return self.begin.as_slice_mut_ptr(self.len());
+  }
+}
+
+impl<T> Default for range<T> {
+  #[inline(always)]
+  fn default() -> range<T> {
+    return range::EMPTY;
+  }
+}
+
+impl<T> From<*const [T]> for range<T> {
+  #[inline(always)]
+  fn from(value: *const [T]) -> range<T> {
+    return range::from_len(ptr::from(value), value.len());
+  }
+}
+
+impl<T> From<*mut [T]> for range<T> {
+  #[inline(always)]
+  fn from(value: *mut [T]) -> range<T> {
+    return range::from_len(ptr::from(value), value.len());
+  }
+}
+
+impl<T> From<&[T]> for range<T> {
+  #[inline(always)]
+  fn from(value: &[T]) -> range<T> {
+    return range::from(value as *const [T]);
+  }
+}
+
+impl<T> From<&mut [T]> for range<T> {
+  #[inline(always)]
+  fn from(value: &mut [T]) -> range<T> {
+    return range::from(value as *mut [T]);
+  }
+}
+
+impl<T> From<range<T>> for *const [T] {
+  #[inline(always)]
+  fn from(value: range<T>) -> *const [T] {
+    return value.as_slice_const_ptr();
+  }
+}
+
+impl<T> From<range<T>> for *mut [T] {
+  #[inline(always)]
+  fn from(value: range<T>) -> *mut [T] {
+    return value.as_slice_mut_ptr();
+  }
+}
+
+impl<T> IntoIterator for range<T> {
+  type Item = ptr<T>;
+  type IntoIter = RangeIter<T>;
+
+  #[inline(always)]
+  fn into_iter(self) -> RangeIter<T> {
+    return self.iter();
+  }
+}
+
+impl<T> core::fmt::Debug for range<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    return write!(f, "{:?}..{:?}", self.begin, self.end);
+  }
+}
+
+/// An iterator over the elements of a [`range`].
+
+pub struct RangeIter<T> {
+  rest: range<T>,
+}
+
+impl<T> RangeIter<T> {
+  /// The elements not yet yielded.
+
+  #[inline(always)]
+  pub fn as_range(&self) -> range<T> {
+    return self.rest;
+  }
+}
+
+impl<T> Clone for RangeIter<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    return RangeIter { rest: self.rest };
+  }
+}
+
+impl<T> Iterator for RangeIter<T> {
+  type Item = ptr<T>;
+
+  #[inline(always)]
+  fn next(&mut self) -> Option<ptr<T>> {
+    if self.rest.is_empty() {
+      return None;
+    }
+
+    let x = self.rest.begin;
+
+    if range::<T>::IS_ZST {
+      self.rest.end = self.rest.end.byte_sub(1);
+    } else {
+      self.rest.begin += 1usize;
+    }
+
+    return Some(x);
+  }
+
+  #[inline(always)]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let n = self.rest.len();
+    return (n, Some(n));
+  }
+}
+
+impl<T> DoubleEndedIterator for RangeIter<T> {
+  #[inline(always)]
+  fn next_back(&mut self) -> Option<ptr<T>> {
+    if self.rest.is_empty() {
+      return None;
+    }
+
+    if range::<T>::IS_ZST {
+      self.rest.end = self.rest.end.byte_sub(1);
+      return Some(self.rest.begin);
+    }
+
+    self.rest.end -= 1usize;
+    return Some(self.rest.end);
+  }
+}
+
+impl<T> ExactSizeIterator for RangeIter<T> {
+}
+
+impl<T> core::iter::FusedIterator for RangeIter<T> {
+}
+
+/// An iterator over consecutive subranges of a [`range`].
+
+pub struct RangeChunks<T> {
+  rest: range<T>,
+  n: usize,
+}
+
+impl<T> Clone for RangeChunks<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    return RangeChunks { rest: self.rest, n: self.n };
+  }
+}
+
+impl<T> Iterator for RangeChunks<T> {
+  type Item = range<T>;
+
+  #[inline(always)]
+  fn next(&mut self) -> Option<range<T>> {
+    if self.rest.is_empty() {
+      return None;
+    }
+
+    let (x, y) = self.rest.split_at(self.n.min(self.rest.len()));
+    self.rest = y;
+    return Some(x);
+  }
+
+  #[inline(always)]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let n = self.rest.len().div_ceil(self.n);
+    return (n, Some(n));
+  }
+}
+
+impl<T> ExactSizeIterator for RangeChunks<T> {
+}
+
+impl<T> core::iter::FusedIterator for RangeChunks<T> {
+}
+
+/// A pointer that advances by a byte stride rather than by `size_of::<T>()`.
+///
+/// The stride may be negative or smaller than `size_of::<T>()`. Element
+/// `i` is at `base.byte_add(i * stride)`, with wrapping arithmetic.
+
+#[allow(non_camel_case_types)]
+pub struct strided<T> {
+  base: ptr<T>,
+  stride: isize,
+}
+
+impl<T> Clone for strided<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    return *self;
+  }
+}
+
+impl<T> Copy for strided<T> {
+}
+
+impl<T> Eq for strided<T> {
+}
+
+impl<T> PartialEq for strided<T> {
+  #[inline(always)]
+  fn eq(&self, other: &Self) -> bool {
+    return self.base == other.base && self.stride == other.stride;
+  }
+}
+
+impl<T> core::hash::Hash for strided<T> {
+  #[inline(always)]
+  fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+    self.base.hash(state);
+    self.stride.hash(state);
+  }
+}
+
+impl<T> strided<T> {
+  /// Creates a strided pointer from a base and a byte stride.
+
+  #[inline(always)]
+  pub const fn new(base: ptr<T>, stride: isize) -> strided<T> {
+    return strided { base, stride };
+  }
+
+  /// Creates a strided pointer whose stride is `size_of::<T>()`.
+
+  #[inline(always)]
+  pub const fn dense(base: ptr<T>) -> strided<T> {
+    return strided::new(base, size_of::<T>() as isize);
+  }
+
+  /// The first element.
+
+  #[inline(always)]
+  pub fn base(self) -> ptr<T> {
+    return self.base;
+  }
+
+  /// The distance between consecutive elements, in bytes.
+
+  #[inline(always)]
+  pub fn stride(self) -> isize {
+    return self.stride;
+  }
+
+  /// The element at `index`.
+
+  #[inline(always)]
+  pub fn at(self, index: usize) -> ptr<T> {
+    return self.base.byte_add((index as isize).wrapping_mul(self.stride) as usize);
+  }
+
+  /// Moves the base forward by `n` elements.
+
+  #[inline(always)]
+  pub fn offset(self, n: usize) -> strided<T> {
+    return strided::new(self.at(n), self.stride);
+  }
+
+  /// The same elements in reverse, ending at the base. The first element of
+  /// the result is `self.at(len - 1)`.
+
+  #[inline(always)]
+  pub fn rev(self, len: usize) -> strided<T> {
+    return strided::new(self.at(len.wrapping_sub(1)), self.stride.wrapping_neg());
+  }
+
+  /// An iterator over the first `len` elements.
+
+  #[inline(always)]
+  pub fn iter(self, len: usize) -> StridedIter<T> {
+    return StridedIter { next: self, len };
+  }
+}
+
+impl<T> From<ptr<T>> for strided<T> {
+  #[inline(always)]
+  fn from(value: ptr<T>) -> strided<T> {
+    return strided::dense(value);
+  }
+}
+
+impl<T> core::fmt::Debug for strided<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    return f.debug_struct("strided").field("base", &self.base).field("stride", &self.stride).finish();
+  }
+}
+
+/// An iterator over the elements of a [`strided`] pointer.
+
+pub struct StridedIter<T> {
+  next: strided<T>,
+  len: usize,
+}
+
+impl<T> Clone for StridedIter<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    return StridedIter { next: self.next, len: self.len };
+  }
+}
+
+impl<T> Iterator for StridedIter<T> {
+  type Item = ptr<T>;
+
+  #[inline(always)]
+  fn next(&mut self) -> Option<ptr<T>> {
+    if self.len == 0 {
+      return None;
+    }
+
+    let x = self.next.base;
+    self.next = self.next.offset(1);
+    self.len -= 1;
+    return Some(x);
+  }
+
+  #[inline(always)]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    return (self.len, Some(self.len));
+  }
+}
+
+impl<T> DoubleEndedIterator for StridedIter<T> {
+  #[inline(always)]
+  fn next_back(&mut self) -> Option<ptr<T>> {
+    if self.len == 0 {
+      return None;
+    }
+
+    self.len -= 1;
+    return Some(self.next.at(self.len));
+  }
+}
+
+impl<T> ExactSizeIterator for StridedIter<T> {
+}
+
+impl<T> core::iter::FusedIterator for StridedIter<T> {
+}
+
+/// A two-dimensional view: `height` rows of `width` elements.
+///
+/// Rows are `pitch` bytes apart and elements within a row are `step` bytes
+/// apart. A view made with [`pitched::new`] has `step == size_of::<T>()`.
+/// Sub-rectangles and transposed views share the same memory.
+
+#[allow(non_camel_case_types)]
+pub struct pitched<T> {
+  base: ptr<T>,
+  pitch: isize,
+  step: isize,
+  width: usize,
+  height: usize,
+}
+
+impl<T> Clone for pitched<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    return *self;
+  }
+}
+
+impl<T> Copy for pitched<T> {
+}
+
+impl<T> Eq for pitched<T> {
+}
+
+impl<T> PartialEq for pitched<T> {
+  #[inline(always)]
+  fn eq(&self, other: &Self) -> bool {
+    return
+      self.base == other.base
+        && self.pitch == other.pitch
+        && self.step == other.step
+        && self.width == other.width
+        && self.height == other.height;
+  }
+}
+
+impl<T> pitched<T> {
+  /// Creates a view of `height` rows of `width` contiguous elements, with
+  /// rows `pitch` bytes apart.
+
+  #[inline(always)]
+  pub const fn new(base: ptr<T>, pitch: isize, width: usize, height: usize) -> pitched<T> {
+    return pitched::from_strides(base, pitch, size_of::<T>() as isize, width, height);
+  }
+
+  /// Creates a view with arbitrary row and element strides, both in bytes.
+
+  #[inline(always)]
+  pub const fn from_strides(base: ptr<T>, pitch: isize, step: isize, width: usize, height: usize) -> pitched<T> {
+    return pitched { base, pitch, step, width, height };
+  }
+
+  /// The element at column zero, row zero.
+
+  #[inline(always)]
+  pub fn base(self) -> ptr<T> {
+    return self.base;
+  }
+
+  /// The distance between rows, in bytes.
+
+  #[inline(always)]
+  pub fn pitch(self) -> isize {
+    return self.pitch;
+  }
+
+  /// The distance between elements within a row, in bytes.
+
+  #[inline(always)]
+  pub fn step(self) -> isize {
+    return self.step;
+  }
+
+  /// The number of columns.
+
+  #[inline(always)]
+  pub fn width(self) -> usize {
+    return self.width;
+  }
+
+  /// The number of rows.
+
+  #[inline(always)]
+  pub fn height(self) -> usize {
+    return self.height;
+  }
+
+  /// Whether the view has no elements.
+
+  #[inline(always)]
+  pub fn is_empty(self) -> bool {
+    return self.width == 0 || self.height == 0;
+  }
+
+  /// The element at column `x`, row `y`. The bounds are checked with
+  /// `debug_assert!`.
+
+  #[inline(always)]
+  pub fn at(self, x: usize, y: usize) -> ptr<T> {
+    debug_assert!(x < self.width && y < self.height);
+
+    return self.row(y).at(x);
+  }
+
+  /// The element at column `x`, row `y`, if it is in the view.
+
+  #[inline(always)]
+  pub fn get(self, x: usize, y: usize) -> Option<ptr<T>> {
+    return if x < self.width && y < self.height { Some(self.at(x, y)) } else { None };
+  }
+
+  /// Row `y`, which has `width` elements.
+
+  #[inline(always)]
+  pub fn row(self, y: usize) -> strided<T> {
+    return strided::new(strided::new(self.base, self.pitch).at(y), self.step);
+  }
+
+  /// Column `x`, which has `height` elements.
+
+  #[inline(always)]
+  pub fn column(self, x: usize) -> strided<T> {
+    return strided::new(strided::new(self.base, self.step).at(x), self.pitch);
+  }
+
+  /// An iterator over the rows, each yielded as an iterator over its
+  /// elements.
+
+  #[inline(always)]
+  pub fn rows(self) -> PitchedLines<T> {
+    let lines = strided::new(self.base, self.pitch).iter(self.height);
+    return PitchedLines { lines, step: self.step, len: self.width };
+  }
+
+  /// An iterator over the columns, each yielded as an iterator over its
+  /// elements.
+
+  #[inline(always)]
+  pub fn columns(self) -> PitchedLines<T> {
+    return self.transpose().rows();
+  }
+
+  /// The sub-rectangle of `width` columns and `height` rows whose top-left
+  /// element is at column `x`, row `y`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the sub-rectangle does not fit in the view.
+
+  #[inline(always)]
+  pub fn sub(self, x: usize, y: usize, width: usize, height: usize) -> pitched<T> {
+    assert!(
+      x <= self.width && width <= self.width - x && y <= self.height && height <= self.height - y,
+      "sub-rectangle out of bounds"
+    );
+
+    let base = self.row(y).at(x);
+    return pitched::from_strides(base, self.pitch, self.step, width, height);
+  }
+
+  /// The transposed view, in which rows are columns and columns are rows.
+
+  #[inline(always)]
+  pub fn transpose(self) -> pitched<T> {
+    return pitched::from_strides(self.base, self.step, self.pitch, self.height, self.width);
+  }
+}
+
+impl<T> core::fmt::Debug for pitched<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    return
+      f.debug_struct("pitched")
+        .field("base", &self.base)
+        .field("pitch", &self.pitch)
+        .field("step", &self.step)
+        .field("width", &self.width)
+        .field("height", &self.height)
+        .finish();
+  }
+}
+
+/// An iterator over the rows or columns of a [`pitched`] view.
+
+pub struct PitchedLines<T> {
+  lines: StridedIter<T>,
+  step: isize,
+  len: usize,
+}
+
+impl<T> Clone for PitchedLines<T> {
+  #[inline(always)]
+  fn clone(&self) -> Self {
+    return PitchedLines { lines: self.lines.clone(), step: self.step, len: self.len };
+  }
+}
+
+impl<T> Iterator for PitchedLines<T> {
+  type Item = StridedIter<T>;
+
+  #[inline(always)]
+  fn next(&mut self) -> Option<StridedIter<T>> {
+    return self.lines.next().map(|x| strided::new(x, self.step).iter(self.len));
+  }
+
+  #[inline(always)]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    return self.lines.size_hint();
+  }
+}
+
+impl<T> DoubleEndedIterator for PitchedLines<T> {
+  #[inline(always)]
+  fn next_back(&mut self) -> Option<StridedIter<T>> {
+    return self.lines.next_back().map(|x| strided::new(x, self.step).iter(self.len));
+  }
+}
+
+impl<T> ExactSizeIterator for PitchedLines<T> {
+}
+
+impl<T> core::iter::FusedIterator for PitchedLines<T> {
+}
+
+/// Primitive types with a byte order.
+///
+/// Conversions go through byte arrays, so a value in the wrong byte order is
+/// never held as a `Self`. For floats this keeps NaN payloads bit-exact on
+/// targets, such as x87, where loading a float can quiet a signaling NaN.
+///
+/// # SAFETY
+///
+/// `Bytes` must be `[u8; size_of::<Self>()]`, every bit pattern must be a
+/// valid value of the type, and the conversions must be the ones of the
+/// inherent methods of the same name.
+
+pub unsafe trait Endian: Copy {
+  /// The bytes of a value.
+
+  type Bytes: Copy;
+
+  /// Converts from little-endian bytes.
+
+  fn from_le_bytes(x: Self::Bytes) -> Self;
+
+  /// Converts from big-endian bytes.
+
+  fn from_be_bytes(x: Self::Bytes) -> Self;
+
+  /// Converts to little-endian bytes.
+
+  fn to_le_bytes(self) -> Self::Bytes;
+
+  /// Converts to big-endian bytes.
+
+  fn to_be_bytes(self) -> Self::Bytes;
+}
+
+macro_rules! endian {
+  ($($t:ty),*) => {
+    $(
+      unsafe impl Endian for $t {
+        type Bytes = [u8; size_of::<$t>()];
+
+        #[inline(always)]
+        fn from_le_bytes(x: Self::Bytes) -> Self {
+          return <$t>::from_le_bytes(x);
+        }
+
+        #[inline(always)]
+        fn from_be_bytes(x: Self::Bytes) -> Self {
+          return <$t>::from_be_bytes(x);
+        }
+
+        #[inline(always)]
+        fn to_le_bytes(self) -> Self::Bytes {
+          return <$t>::to_le_bytes(self);
+        }
+
+        #[inline(always)]
+        fn to_be_bytes(self) -> Self::Bytes {
+          return <$t>::to_be_bytes(self);
+        }
+      }
+    )*
+  };
+}
+
+endian!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl ptr<u8> {
+  /// Reads a little-endian value at any alignment.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::read_unaligned].
+
+  #[inline(always)]
+  pub unsafe fn read_le<T: Endian>(self) -> T {
+    return T::from_le_bytes(unsafe { self.cast::<T::Bytes>().read() });
+  }
+
+  /// Reads a big-endian value at any alignment.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::read_unaligned].
+
+  #[inline(always)]
+  pub unsafe fn read_be<T: Endian>(self) -> T {
+    return T::from_be_bytes(unsafe { self.cast::<T::Bytes>().read() });
+  }
+
+  /// Writes a value as little endian at any alignment.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::write_unaligned].
+
+  #[inline(always)]
+  pub unsafe fn write_le<T: Endian>(self, value: T) {
+    unsafe { self.cast::<T::Bytes>().write(value.to_le_bytes()) };
+  }
+
+  /// Writes a value as big endian at any alignment.
+  ///
+  /// # SAFETY
+  ///
+  /// See [core::ptr::write_unaligned].
+
+  #[inline(always)]
+  pub unsafe fn write_be<T: Endian>(self, value: T) {
+    unsafe { self.cast::<T::Bytes>().write(value.to_be_bytes()) };
+  }
+}
+
+macro_rules! endian_wrapper {
+  ($name:ident, $from:ident, $to:ident, $doc:literal) => {
+    #[doc = $doc]
+    ///
+    /// The wrapper holds the bytes and has alignment one, so it can be read
+    /// and written through a `ptr` at any address. [`Self::get`] yields the
+    /// native value.
+
+    #[allow(non_camel_case_types)]
+    #[repr(transparent)]
+    pub struct $name<T: Endian>(T::Bytes);
+
+    impl<T: Endian> Clone for $name<T> {
+      #[inline(always)]
+      fn clone(&self) -> Self {
+        return *self;
+      }
+    }
+
+    impl<T: Endian> Copy for $name<T> {
+    }
+
+    impl<T: Endian + Eq> Eq for $name<T> {
+    }
+
+    impl<T: Endian + PartialEq> PartialEq for $name<T> {
+      #[inline(always)]
+      fn eq(&self, other: &Self) -> bool {
+        return self.get() == other.get();
+      }
+    }
+
+    impl<T: Endian + core::hash::Hash> core::hash::Hash for $name<T> {
+      #[inline(always)]
+      fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.get().hash(state);
+      }
+    }
+
+    impl<T: Endian> $name<T> {
+      /// Stores a native value.
+
+      #[inline(always)]
+      pub fn new(value: T) -> $name<T> {
+        return $name(value.$to());
+      }
+
+      /// The native value.
+
+      #[inline(always)]
+      pub fn get(self) -> T {
+        return T::$from(self.0);
+      }
+
+      /// Replaces the stored value with a native value.
+
+      #[inline(always)]
+      pub fn set(&mut self, value: T) {
+        self.0 = value.$to();
+      }
+    }
+
+    impl<T: Endian> ptr<$name<T>> {
+      /// Reads the native value.
+      ///
+      /// # SAFETY
+      ///
+      /// See [core::ptr::read].
+
+      #[inline(always)]
+      pub unsafe fn read_native(self) -> T {
+        return unsafe { self.read() }.get();
+      }
+
+      /// Writes a native value.
+      ///
+      /// # SAFETY
+      ///
+      /// See [core::ptr::write].
+
+      #[inline(always)]
+      pub unsafe fn write_native(self, value: T) {
+        unsafe { self.write($name::new(value)) };
+      }
+    }
+
+    impl<T: Endian + Default> Default for $name<T> {
+      #[inline(always)]
+      fn default() -> $name<T> {
+        return $name::new(T::default());
+      }
+    }
+
+    impl<T: Endian> From<T> for $name<T> {
+      #[inline(always)]
+      fn from(value: T) -> $name<T> {
+        return $name::new(value);
+      }
+    }
+
+    impl<T: Endian + core::fmt::Debug> core::fmt::Debug for $name<T> {
+      fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        return self.get().fmt(f);
+      }
+    }
+  };
+}
+
+endian_wrapper!(le, from_le_bytes, to_le_bytes, "A value stored in little-endian byte order.");
+endian_wrapper!(be, from_be_bytes, to_be_bytes, "A value stored in big-endian byte order.");
+
+#[cfg(feature = "derive")]
+pub use pop_derive::Fields;
+
+/// Projects a `ptr<S>` to a `ptr<F>` for a field of `S`.
+///
+/// The field path may name nested fields and array elements, as in
+/// `project!(p, a.b[i].c)`. No intermediate references are created. The
+/// result has the provenance of `p`.
+///
+/// This must be called in an `unsafe` context, because it dereferences `p`
+/// in a place expression. `p` must be in bounds of an allocation; see
+/// [core::ptr::addr_of_mut]. An array index is bounds checked.
+///
+/// ```
+/// use pop::ptr;
+///
+/// struct S { a: u8, b: [(u16, u32); 2] }
+///
+/// let mut s = S { a: 0, b: [(1, 2), (3, 4)] };
+/// let p = ptr::from(&mut s);
+/// let q: ptr<u32> = unsafe { pop::project!(p, b[1].1) };
+/// assert_eq!(unsafe { q.read() }, 4);
+/// ```
+
+#[macro_export]
+macro_rules! project {
+  ($p:expr, $($path:tt)+) => {
+    $crate::ptr::from(&raw mut (*$crate::ptr::as_mut_ptr($p)).$($path)+)
+  };
+}
+
+/// Recovers a `ptr<S>` from a `ptr<F>` to a field of `S`, the inverse of
+/// [`project!`].
+///
+/// The field path may name nested fields, but not array elements. The
+/// pointer's type is checked against the field's type. The computation wraps
+/// around like `ptr`'s arithmetic, so this is safe to call.
+///
+/// ```
+/// use pop::ptr;
+///
+/// struct Node { value: u64, link: (usize, usize) }
+///
+/// let mut x = Node { value: 7, link: (0, 0) };
+/// let p = ptr::from(&mut x.link.1);
+/// let q = pop::container_of!(p, Node, link.1);
+/// assert_eq!(q, ptr::from(&mut x));
+/// ```
+
+#[macro_export]
+macro_rules! container_of {
+  ($p:expr, $t:ty, $($path:tt)+) => {
+    $crate::__macro_support::container_of::<$t, _>(
+      $p,
+      ::core::mem::offset_of!($t, $($path)+),
+      |x: &$t| &x.$($path)+,
+    )
+  };
+}
+
+#[doc(hidden)]
+pub mod __macro_support {
+  //! Implementation details of the exported macros. Not public API.
+
+  use super::ptr;
+
+  /// Steps back from a field to its container. The function argument is
+  /// never called. It only ties the field's type to `T`.
+
+  #[inline(always)]
+  pub fn container_of<S, T>(x: ptr<T>, offset: usize, _: fn(&S) -> &T) -> ptr<S> {
+    return x.byte_sub(offset);
+  }
+}
+
+/// An error returned by a fallible allocation.
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AllocError {
+  /// The allocator could not satisfy a request for the given layout.
+  OutOfMemory(core::alloc::Layout),
+  /// The requested size overflows `Layout`.
+  ///
+  /// This variant carries no layout because there is none to carry: the
+  /// requested size does not fit in a `Layout`, so it cannot be represented
+  /// as one.
+  CapacityOverflow,
+}
+
+impl AllocError {
+  /// The requested layout, if it could be represented. This is `None` for
+  /// [`AllocError::CapacityOverflow`].
+
+  #[inline(always)]
+  pub fn layout(self) -> Option<core::alloc::Layout> {
+    return match self {
+      AllocError::OutOfMemory(layout) => Some(layout),
+      AllocError::CapacityOverflow => None,
+    };
+  }
+}
+
+impl core::fmt::Display for AllocError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    return match self {
+      AllocError::OutOfMemory(layout) =>
+        write!(f, "memory allocation of {} bytes with alignment {} failed", layout.size(), layout.align()),
+      AllocError::CapacityOverflow =>
+        write!(f, "memory allocation size overflows layout"),
+    };
+  }
+}
+
+impl core::error::Error for AllocError {
+}
+
+#[inline(always)]
+fn slice_layout<T>(count: usize) -> Result<core::alloc::Layout, AllocError> {
+  return core::alloc::Layout::array::<T>(count).map_err(|_| AllocError::CapacityOverflow);
+}
+
+/// A source of memory, expressed in terms of [`ptr`].
+///
+/// Only [`Allocator::alloc_layout`] and [`Allocator::dealloc_layout`] are
+/// required. The remaining methods have default implementations in terms of
+/// those two.
+///
+/// # SAFETY
+///
+/// A successful allocation must return a block that is valid for reads and
+/// writes of the requested layout, that does not overlap any other live block,
+/// and that stays valid until it is deallocated.
+
+pub unsafe trait Allocator {
+  /// Allocates memory.
+  ///
+  /// # SAFETY
+  ///
+  /// `layout` must have non-zero size.
+
+  unsafe fn alloc_layout<T>(&self, layout: core::alloc::Layout) -> Result<ptr<T>, AllocError>;
+
+  /// Deallocates memory.
+  ///
+  /// # SAFETY
+  ///
+  /// `x` must be currently allocated by this allocator with `layout`.
+
+  unsafe fn dealloc_layout<T>(&self, x: ptr<T>, layout: core::alloc::Layout);
+
+  /// Allocates zero-initialized memory.
+  ///
+  /// # SAFETY
+  ///
+  /// `layout` must have non-zero size.
+
+  unsafe fn alloc_layout_zeroed<T>(&self, layout: core::alloc::Layout) -> Result<ptr<T>, AllocError> {
+    let x = unsafe { self.alloc_layout::<u8>(layout) }?;
+    unsafe { x.write_bytes(0, layout.size()) };
+    return Ok(x.cast());
+  }
+
+  /// Reallocates memory. On failure, does not alter the old block.
+  ///
+  /// # SAFETY
+  ///
+  /// - `x` must be currently allocated by this allocator with `layout`,
+  /// - `new_size` must be non-zero, and
+  /// - `new_size` rounded up to `layout.align()` must not overflow `isize`.
+
+  unsafe fn realloc_layout<T>(&self, x: ptr<T>, layout: core::alloc::Layout, new_size: usize) -> Result<ptr<T>, AllocError> {
+    let new_layout = unsafe { core::alloc::Layout::from_size_align_unchecked(new_size, layout.align()) };
+    let y = unsafe { self.alloc_layout::<u8>(new_layout) }?;
+    unsafe { y.copy_from_nonoverlapping(x.cast(), layout.size().min(new_size)) };
+    unsafe { self.dealloc_layout(x, layout) };
+    return Ok(y.cast());
+  }
+
+  /// Allocates memory for a `T`.
+  ///
+  /// # SAFETY
+  ///
+  /// `T` must have non-zero size.
+
+  unsafe fn alloc<T>(&self) -> Result<ptr<T>, AllocError> {
+    debug_assert!(size_of::<T>() != 0);
+
+    return unsafe { self.alloc_layout(core::alloc::Layout::new::<T>()) };
+  }
+
+  /// Allocates zero-initialized memory for a `T`.
+  ///
+  /// # SAFETY
+  ///
+  /// `T` must have non-zero size.
+
+  unsafe fn alloc_zeroed<T>(&self) -> Result<ptr<T>, AllocError> {
+    debug_assert!(size_of::<T>() != 0);
+
+    return unsafe { self.alloc_layout_zeroed(core::alloc::Layout::new::<T>()) };
+  }
+
+  /// Allocates memory for a slice of `count` `T`s.
+  ///
+  /// # SAFETY
+  ///
+  /// - `T` must have non-zero size, and
+  /// - `count` must be non-zero.
+
+  unsafe fn alloc_slice<T>(&self, count: usize) -> Result<ptr<T>, AllocError> {
+    debug_assert!(size_of::<T>() != 0);
+    debug_assert!(count != 0);
+
+    return unsafe { self.alloc_layout(slice_layout::<T>(count)?) };
+  }
+
+  /// Allocates zero-initialized memory for a slice of `count` `T`s.
+  ///
+  /// # SAFETY
+  ///
+  /// - `T` must have non-zero size, and
+  /// - `count` must be non-zero.
+
+  unsafe fn alloc_slice_zeroed<T>(&self, count: usize) -> Result<ptr<T>, AllocError> {
+    debug_assert!(size_of::<T>() != 0);
+    debug_assert!(count != 0);
+
+    return unsafe { self.alloc_layout_zeroed(slice_layout::<T>(count)?) };
+  }
+
+  /// Deallocates memory for a `T`.
+  ///
+  /// # SAFETY
+  ///
+  /// `x` must be currently allocated by this allocator for a `T`.
+
+  unsafe fn dealloc<T>(&self, x: ptr<T>) {
+    unsafe { self.dealloc_layout(x, core::alloc::Layout::new::<T>()) };
+  }
+
+  /// Deallocates memory for a slice of `count` `T`s.
+  ///
+  /// # SAFETY
+  ///
+  /// `x` must be currently allocated by this allocator for a slice of `count`
+  /// `T`s.
+
+  unsafe fn dealloc_slice<T>(&self, x: ptr<T>, count: usize) {
+    let align = align_of::<T>();
+    let size = count * size_of::<T>();
+    let layout = unsafe { core::alloc::Layout::from_size_align_unchecked(size, align) };
+    unsafe { self.dealloc_layout(x, layout) };
+  }
+
+  /// Reallocates memory for a slice. On failure, does not alter the old
+  /// block.
+  ///
+  /// # SAFETY
+  ///
+  /// - `x` must be currently allocated by this allocator for a slice of
+  ///   `old_count` `T`s, and
+  /// - `new_count` must be non-zero.
+
+  unsafe fn realloc_slice<T>(&self, x: ptr<T>, old_count: usize, new_count: usize) -> Result<ptr<T>, AllocError> {
+    debug_assert!(new_count != 0);
+
+    let align = align_of::<T>();
+    let old_size = old_count * size_of::<T>();
+    let layout = unsafe { core::alloc::Layout::from_size_align_unchecked(old_size, align) };
+    let new_layout = slice_layout::<T>(new_count)?;
+    return unsafe { self.realloc_layout(x, layout, new_layout.size()) };
+  }
+}
+
+unsafe impl<A: Allocator> Allocator for &A {
+  #[inline(always)]
+  unsafe fn alloc_layout<T>(&self, layout: core::alloc::Layout) -> Result<ptr<T>, AllocError> {
+    return unsafe { (**self).alloc_layout(layout) };
+  }
+
+  #[inline(always)]
+  unsafe fn dealloc_layout<T>(&self, x: ptr<T>, layout: core::alloc::Layout) {
+    unsafe { (**self).dealloc_layout(x, layout) };
+  }
+
+  #[inline(always)]
+  unsafe fn alloc_layout_zeroed<T>(&self, layout: core::alloc::Layout) -> Result<ptr<T>, AllocError> {
+    return unsafe { (**self).alloc_layout_zeroed(layout) };
+  }
+
+  #[inline(always)]
+  unsafe fn realloc_layout<T>(&self, x: ptr<T>, layout: core::alloc::Layout, new_size: usize) -> Result<ptr<T>, AllocError> {
+    return unsafe { (**self).realloc_layout(x, layout, new_size) };
+  }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+pub mod atomic {
+  //! Atomic pointers.
+
+  use core::marker::PhantomData;
+  use core::sync::atomic::Ordering;
+  use super::ptr;
+
+  /// A [`ptr`] that can be shared between threads.
+  ///
+  /// The bitwise and byte-offset operations keep provenance, so they can be
+  /// used to manipulate tag bits, as in [`tagged`](super::tagged).
+
+  #[repr(transparent)]
+  pub struct AtomicPtr<T>(core::sync::atomic::AtomicPtr<u8>, PhantomData<fn(T) -> T>);
+
+  impl<T> AtomicPtr<T> {
+    /// Creates a new atomic pointer.
+
+    #[inline(always)]
+    pub const fn new(x: ptr<T>) -> AtomicPtr<T> {
+      return AtomicPtr(core::sync::atomic::AtomicPtr::new(x.0), PhantomData);
+    }
+
+    /// Consumes the atomic and returns the contained pointer.
+
+    #[inline(always)]
+    pub fn into_inner(self) -> ptr<T> {
+      return ptr(self.0.into_inner(), PhantomData);
+    }
+
+    /// Accesses the contained pointer through a unique reference.
+
+    #[inline(always)]
+    pub fn get_mut(&mut self) -> &mut ptr<T> {
+      return unsafe { ptr::from(self.0.get_mut()).cast::<ptr<T>>().as_mut_ref() };
+    }
+
+    /// Loads the pointer.
+
+    #[inline(always)]
+    pub fn load(&self, order: Ordering) -> ptr<T> {
+      return ptr(self.0.load(order), PhantomData);
+    }
+
+    /// Stores a pointer.
+
+    #[inline(always)]
+    pub fn store(&self, x: ptr<T>, order: Ordering) {
+      self.0.store(x.0, order);
+    }
+
+    /// Stores a pointer, returning the previous pointer.
+
+    #[inline(always)]
+    pub fn swap(&self, x: ptr<T>, order: Ordering) -> ptr<T> {
+      return ptr(self.0.swap(x.0, order), PhantomData);
+    }
+
+    /// Stores `new` if the current pointer is `current`. Returns the previous
+    /// pointer, wrapped in `Ok` on success and `Err` on failure.
+
+    #[inline(always)]
+    pub fn compare_exchange(&self, current: ptr<T>, new: ptr<T>, success: Ordering, failure: Ordering) -> Result<ptr<T>, ptr<T>> {
+      return
+        self.0.compare_exchange(current.0, new.0, success, failure)
+          .map(|x| ptr(x, PhantomData))
+          .map_err(|x| ptr(x, PhantomData));
+    }
+
+    /// Like [`AtomicPtr::compare_exchange`], but may fail spuriously.
+
+    #[inline(always)]
+    pub fn compare_exchange_weak(&self, current: ptr<T>, new: ptr<T>, success: Ordering, failure: Ordering) -> Result<ptr<T>, ptr<T>> {
+      return
+        self.0.compare_exchange_weak(current.0, new.0, success, failure)
+          .map(|x| ptr(x, PhantomData))
+          .map_err(|x| ptr(x, PhantomData));
+    }
+
+    /// Repeatedly applies `f` to the current pointer and tries to store the
+    /// result, until `f` returns `None` or the store succeeds. Returns the
+    /// previous pointer, wrapped in `Ok` on success and `Err` on failure.
+
+    #[inline(always)]
+    pub fn fetch_update<F>(&self, set_order: Ordering, fetch_order: Ordering, mut f: F) -> Result<ptr<T>, ptr<T>>
+    where
+      F: FnMut(ptr<T>) -> Option<ptr<T>>
+    {
+      return
+        self.0.fetch_update(set_order, fetch_order, |x| f(ptr(x, PhantomData)).map(|y| y.0))
+          .map(|x| ptr(x, PhantomData))
+          .map_err(|x| ptr(x, PhantomData));
+    }
+
+    /// Adds `n` bytes to the address, wrapping around, and returns the
+    /// previous pointer.
+
+    #[inline(always)]
+    pub fn fetch_byte_add(&self, n: usize, order: Ordering) -> ptr<T> {
+      return ptr(self.0.fetch_byte_add(n, order), PhantomData);
+    }
+
+    /// Subtracts `n` bytes from the address, wrapping around, and returns the
+    /// previous pointer.
+
+    #[inline(always)]
+    pub fn fetch_byte_sub(&self, n: usize, order: Ordering) -> ptr<T> {
+      return ptr(self.0.fetch_byte_sub(n, order), PhantomData);
+    }
+
+    /// Bitwise-ors the address with `bits` and returns the previous pointer.
+
+    #[inline(always)]
+    pub fn fetch_or(&self, bits: usize, order: Ordering) -> ptr<T> {
+      return ptr(self.0.fetch_or(bits, order), PhantomData);
+    }
+
+    /// Bitwise-ands the address with `bits` and returns the previous pointer.
+
+    #[inline(always)]
+    pub fn fetch_and(&self, bits: usize, order: Ordering) -> ptr<T> {
+      return ptr(self.0.fetch_and(bits, order), PhantomData);
+    }
+
+    /// Bitwise-xors the address with `bits` and returns the previous pointer.
+
+    #[inline(always)]
+    pub fn fetch_xor(&self, bits: usize, order: Ordering) -> ptr<T> {
+      return ptr(self.0.fetch_xor(bits, order), PhantomData);
+    }
+  }
+
+  impl<T> Default for AtomicPtr<T> {
+    #[inline(always)]
+    fn default() -> AtomicPtr<T> {
+      return AtomicPtr::new(ptr::NULL);
+    }
+  }
+
+  impl<T> From<ptr<T>> for AtomicPtr<T> {
+    #[inline(always)]
+    fn from(value: ptr<T>) -> AtomicPtr<T> {
+      return AtomicPtr::new(value);
+    }
+  }
+
+  impl<T> core::fmt::Debug for AtomicPtr<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return core::fmt::Debug::fmt(&self.load(Ordering::Relaxed), f);
+    }
+  }
+
+  #[derive(Clone, Copy)]
+  #[repr(C)]
+  struct Pair {
+    ptr: *mut u8,
+    count: usize,
+  }
+
+  /// A [`ptr`] paired with a counter, both of which are updated together by
+  /// a double-word compare-and-swap.
+  ///
+  /// Bumping the counter on every update defeats the ABA problem in
+  /// lock-free structures.
+  ///
+  /// On x86_64, operations use `cmpxchg16b` if the CPU supports it, which is
+  /// checked at compile time or, failing that, once at runtime. On other
+  /// targets, or if the CPU lacks `cmpxchg16b`, operations instead take one
+  /// of a fixed set of global spin locks chosen by address, so they are
+  /// atomic with respect to each other but not lock-free.
+  ///
+  /// All operations are sequentially consistent.
+
+  #[repr(C, align(16))]
+  pub struct AtomicPtrCounter<T>(core::cell::UnsafeCell<Pair>, PhantomData<fn(T) -> T>);
+
+  unsafe impl<T> Send for AtomicPtrCounter<T> {
+  }
+
+  unsafe impl<T> Sync for AtomicPtrCounter<T> {
+  }
+
+  impl<T> AtomicPtrCounter<T> {
+    /// Creates a new atomic pointer and counter.
+
+    #[inline(always)]
+    pub const fn new(x: ptr<T>, count: usize) -> AtomicPtrCounter<T> {
+      return AtomicPtrCounter(core::cell::UnsafeCell::new(Pair { ptr: x.0, count }), PhantomData);
+    }
+
+    /// Consumes the atomic and returns the contained pointer and counter.
+
+    #[inline(always)]
+    pub fn into_inner(self) -> (ptr<T>, usize) {
+      let Pair { ptr: x, count } = self.0.into_inner();
+      return (ptr(x, PhantomData), count);
+    }
+
+    /// Whether operations are lock-free, i.e. use `cmpxchg16b`.
+
+    #[inline(always)]
+    pub fn is_lock_free() -> bool {
+      return has_cmpxchg16b();
+    }
+
+    /// Loads the pointer and counter.
+
+    #[inline(always)]
+    pub fn load(&self) -> (ptr<T>, usize) {
+      let x = self.0.get();
+
+      #[cfg(target_arch = "x86_64")]
+      if has_cmpxchg16b() {
+        // A compare-and-swap that replaces a value with itself, if it
+        // happens to match, is an atomic load.
+
+        let zero = Pair { ptr: core::ptr::null_mut(), count: 0 };
+        let (Ok(y) | Err(y)) = unsafe { cmpxchg16b(x, zero, zero) };
+        return (ptr(y.ptr, PhantomData), y.count);
+      }
+
+      let lock = Lock::acquire(x.addr());
+      let y = unsafe { x.read() };
+      lock.release();
+      return (ptr(y.ptr, PhantomData), y.count);
+    }
+
+    /// Stores a pointer and counter.
+
+    #[inline(always)]
+    pub fn store(&self, new: (ptr<T>, usize)) {
+      let _ = self.swap(new);
+    }
+
+    /// Stores a pointer and counter, returning the previous ones.
+
+    #[inline(always)]
+    pub fn swap(&self, new: (ptr<T>, usize)) -> (ptr<T>, usize) {
+      let x = self.0.get();
+      let new = Pair { ptr: new.0.0, count: new.1 };
+
+      #[cfg(target_arch = "x86_64")]
+      if has_cmpxchg16b() {
+        // The first guess only needs to be some value. A failed exchange
+        // yields the current one.
+
+        let mut y = Pair { ptr: core::ptr::null_mut(), count: 0 };
+
+        while let Err(z) = unsafe { cmpxchg16b(x, y, new) } {
+          y = z;
+        }
+
+        return (ptr(y.ptr, PhantomData), y.count);
+      }
+
+      let lock = Lock::acquire(x.addr());
+      let y = unsafe { x.replace(new) };
+      lock.release();
+      return (ptr(y.ptr, PhantomData), y.count);
+    }
+
+    /// Stores `new` if the current pointer and counter are `current`.
+    /// Returns the previous pointer and counter, wrapped in `Ok` on success
+    /// and `Err` on failure.
+
+    #[inline(always)]
+    pub fn compare_exchange(&self, current: (ptr<T>, usize), new: (ptr<T>, usize)) -> Result<(ptr<T>, usize), (ptr<T>, usize)> {
+      let x = self.0.get();
+      let current = Pair { ptr: current.0.0, count: current.1 };
+      let new = Pair { ptr: new.0.0, count: new.1 };
+
+      #[cfg(target_arch = "x86_64")]
+      if has_cmpxchg16b() {
+        return
+          unsafe { cmpxchg16b(x, current, new) }
+            .map(|y| (ptr(y.ptr, PhantomData), y.count))
+            .map_err(|y| (ptr(y.ptr, PhantomData), y.count));
+      }
+
+      let lock = Lock::acquire(x.addr());
+      let y = unsafe { x.read() };
+      let ok = y.ptr == current.ptr && y.count == current.count;
+
+      if ok {
+        unsafe { x.write(new) };
+      }
+
+      lock.release();
+
+      let y = (ptr(y.ptr, PhantomData), y.count);
+      return if ok { Ok(y) } else { Err(y) };
+    }
+  }
+
+  impl<T> Default for AtomicPtrCounter<T> {
+    #[inline(always)]
+    fn default() -> AtomicPtrCounter<T> {
+      return AtomicPtrCounter::new(ptr::NULL, 0);
+    }
+  }
+
+  impl<T> core::fmt::Debug for AtomicPtrCounter<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return core::fmt::Debug::fmt(&self.load(), f);
+    }
+  }
+
+  #[cfg(target_arch = "x86_64")]
+  #[inline(always)]
+  fn has_cmpxchg16b() -> bool {
+    #[cfg(target_feature = "cmpxchg16b")]
+    return true;
+
+    #[cfg(not(target_feature = "cmpxchg16b"))]
+    {
+      use core::sync::atomic::AtomicU8;
+
+      const UNKNOWN: u8 = 0;
+      const YES: u8 = 1;
+      const NO: u8 = 2;
+
+      static STATE: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+      let mut state = STATE.load(Ordering::Relaxed);
+
+      if state == UNKNOWN {
+        state = if core::arch::x86_64::__cpuid(1).ecx & 1 << 13 != 0 { YES } else { NO };
+        STATE.store(state, Ordering::Relaxed);
+      }
+
+      return state == YES;
+    }
+  }
+
+  #[cfg(not(target_arch = "x86_64"))]
+  #[inline(always)]
+  fn has_cmpxchg16b() -> bool {
+    return false;
+  }
+
+  #[cfg(target_arch = "x86_64")]
+  #[inline(always)]
+  unsafe fn cmpxchg16b(x: *mut Pair, current: Pair, new: Pair) -> Result<Pair, Pair> {
+    let ok: u8;
+    let y: *mut u8;
+    let count: usize;
+
+    // `rbx` is reserved by LLVM, so the low word of `new` is swapped into it
+    // around the instruction.
+
+    unsafe {
+      core::arch::asm!(
+        "xchg {tmp}, rbx",
+        "lock cmpxchg16b xmmword ptr [{x}]",
+        "sete {ok}",
+        "mov rbx, {tmp}",
+        x = in(reg) x,
+        tmp = inout(reg) new.ptr => _,
+        ok = out(reg_byte) ok,
+        inout("rax") current.ptr => y,
+        inout("rdx") current.count => count,
+        in("rcx") new.count,
+        options(nostack),
+      )
+    };
+
+    let y = Pair { ptr: y, count };
+    return if ok != 0 { Ok(y) } else { Err(y) };
+  }
+
+  struct Lock(&'static core::sync::atomic::AtomicBool);
+
+  impl Lock {
+    #[inline(always)]
+    fn acquire(addr: usize) -> Lock {
+      use core::sync::atomic::AtomicBool;
+
+      static LOCKS: [AtomicBool; 64] = [const { AtomicBool::new(false) }; 64];
+
+      let lock = &LOCKS[addr / 16 % LOCKS.len()];
+
+      while lock.swap(true, Ordering::SeqCst) {
+        while lock.load(Ordering::Relaxed) {
+          core::hint::spin_loop();
+        }
+      }
+
+      return Lock(lock);
+    }
+
+    #[inline(always)]
+    fn release(self) {
+      self.0.store(false, Ordering::SeqCst);
+    }
+  }
+}
+
+pub mod rel {
+  //! Self-relative pointers.
+
+  use core::marker::PhantomData;
+  use super::ptr;
+
+  /// A signed integer type that can hold a [`relptr`] offset.
+
+  pub trait Offset: Copy + Eq + core::fmt::Debug {
+    /// The zero offset, which represents null.
+
+    const ZERO: Self;
+
+    /// Converts to an `isize`.
+
+    fn to_isize(self) -> isize;
+
+    /// Converts from an `isize`, if it fits.
+
+    fn from_isize(n: isize) -> Option<Self>;
+  }
+
+  impl Offset for i8 {
+    const ZERO: i8 = 0;
+
+    #[inline(always)]
+    fn to_isize(self) -> isize {
+      return self as isize;
+    }
+
+    #[inline(always)]
+    fn from_isize(n: isize) -> Option<i8> {
+      return i8::try_from(n).ok();
+    }
+  }
+
+  impl Offset for i16 {
+    const ZERO: i16 = 0;
+
+    #[inline(always)]
+    fn to_isize(self) -> isize {
+      return self as isize;
+    }
+
+    #[inline(always)]
+    fn from_isize(n: isize) -> Option<i16> {
+      return i16::try_from(n).ok();
+    }
+  }
+
+  impl Offset for i32 {
+    const ZERO: i32 = 0;
+
+    #[inline(always)]
+    fn to_isize(self) -> isize {
+      return self as isize;
+    }
+
+    #[inline(always)]
+    fn from_isize(n: isize) -> Option<i32> {
+      return i32::try_from(n).ok();
+    }
+  }
+
+  impl Offset for i64 {
+    const ZERO: i64 = 0;
+
+    #[inline(always)]
+    fn to_isize(self) -> isize {
+      return self as isize;
+    }
+
+    #[inline(always)]
+    fn from_isize(n: isize) -> Option<i64> {
+      return i64::try_from(n).ok();
+    }
+  }
+
+  impl Offset for isize {
+    const ZERO: isize = 0;
+
+    #[inline(always)]
+    fn to_isize(self) -> isize {
+      return self;
+    }
+
+    #[inline(always)]
+    fn from_isize(n: isize) -> Option<isize> {
+      return Some(n);
+    }
+  }
+
+  /// A pointer stored as the signed byte offset from its own location to its
+  /// target.
+  ///
+  /// Moving a buffer as a whole, whether by copying its bytes or by mapping
+  /// it at a different address, preserves every `relptr` whose location and
+  /// target are both inside it. Moving a `relptr` on its own does not, so it
+  /// is neither `Clone` nor `Copy`.
+  ///
+  /// Offset zero represents null, so a non-null `relptr` cannot point to
+  /// itself. A zeroed `relptr` is null.
+  ///
+  /// The methods taking `&self` or `&mut self` derive the target from the
+  /// reference, so the resulting pointer only has the reference's provenance.
+  /// To dereference the target, use [`relptr::resolve`] with a pointer that
+  /// has provenance over the whole buffer.
+
+  #[allow(non_camel_case_types)]
+  #[repr(transparent)]
+  pub struct relptr<T, O: Offset = i32>(O, PhantomData<fn(T) -> T>);
+
+  impl<T, O: Offset> relptr<T, O> {
+    /// The null pointer.
+
+    pub const NULL: relptr<T, O> = relptr(O::ZERO, PhantomData);
+
+    /// The stored offset.
+
+    #[inline(always)]
+    pub fn offset(&self) -> O {
+      return self.0;
+    }
+
+    /// Whether the pointer is null.
+
+    #[inline(always)]
+    pub fn is_null(&self) -> bool {
+      return self.0 == O::ZERO;
+    }
+
+    #[inline(always)]
+    fn target(this: ptr<relptr<T, O>>, offset: O) -> ptr<T> {
+      if offset == O::ZERO {
+        return ptr::NULL;
+      }
+
+      return this.byte_add(offset.to_isize() as usize);
+    }
+
+    #[inline(always)]
+    fn encode(this: ptr<relptr<T, O>>, x: ptr<T>) -> Option<O> {
+      if x.is_null() {
+        return Some(O::ZERO);
+      }
+
+      let n = x.byte_diff(this) as isize;
+
+      if n == 0 {
+        return None;
+      }
+
+      return O::from_isize(n);
+    }
+
+    /// The target, with the provenance of `self`.
+
+    #[inline(always)]
+    pub fn get(&self) -> ptr<T> {
+      return Self::target(ptr::from(self), self.0);
+    }
+
+    /// Points to `x`.
+    ///
+    /// Panics if the offset does not fit in `O` or if `x` is the location of
+    /// `self`.
+
+    #[inline(always)]
+    pub fn set(&mut self, x: ptr<T>) {
+      if ! self.try_set(x) {
+        panic!("relative pointer offset out of range");
+      }
+    }
+
+    /// Points to `x`. Returns `false`, leaving `self` unchanged, if the
+    /// offset does not fit in `O` or if `x` is the location of `self`.
+
+    #[inline(always)]
+    pub fn try_set(&mut self, x: ptr<T>) -> bool {
+      match Self::encode(ptr::from(&*self), x) {
+        Some(offset) => {
+          self.0 = offset;
+          return true;
+        }
+        None => {
+          return false;
+        }
+      }
+    }
+
+    /// The target of the `relptr` at `this`, with the provenance of `this`.
+    ///
+    /// # SAFETY
+    ///
+    /// See [core::ptr::read].
+
+    #[inline(always)]
+    pub unsafe fn resolve(this: ptr<relptr<T, O>>) -> ptr<T> {
+      return Self::target(this, unsafe { this.cast::<O>().read() });
+    }
+
+    /// Points the `relptr` at `this` to `x`.
+    ///
+    /// Panics if the offset does not fit in `O` or if `x` is `this`.
+    ///
+    /// # SAFETY
+    ///
+    /// See [core::ptr::write].
+
+    #[inline(always)]
+    pub unsafe fn store(this: ptr<relptr<T, O>>, x: ptr<T>) {
+      match Self::encode(this, x) {
+        Some(offset) => unsafe { this.cast::<O>().write(offset) },
+        None => panic!("relative pointer offset out of range"),
+      }
+    }
+  }
+
+  impl<T, O: Offset> Default for relptr<T, O> {
+    #[inline(always)]
+    fn default() -> relptr<T, O> {
+      return relptr::NULL;
+    }
+  }
+
+  impl<T, O: Offset> core::fmt::Debug for relptr<T, O> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return f.debug_tuple("relptr").field(&self.0).finish();
+    }
+  }
+}
+
+pub mod cursor {
+  //! Bounds-checked byte cursors.
+  //!
+  //! A [`Reader`] or [`Writer`] walks a region of known length. Every
+  //! operation checks the remaining length and returns an [`Error`] instead
+  //! of reading or writing past the end. A failed operation leaves the
+  //! position unchanged.
+
+  use super::Endian;
+  use super::ptr;
+  use super::range;
+
+  /// An error returned by a cursor operation.
+
+  #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+  pub enum Error {
+    /// The operation needed more bytes than remain.
+    Overrun {
+      /// The number of bytes the operation needed.
+      needed: usize,
+      /// The number of bytes that remained.
+      remaining: usize,
+    },
+    /// A varint or length prefix does not fit its type.
+    Overflow,
+  }
+
+  impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return match self {
+        Error::Overrun { needed, remaining } =>
+          write!(f, "cursor overrun: needed {} bytes but {} remain", needed, remaining),
+        Error::Overflow =>
+          write!(f, "value does not fit its type"),
+      };
+    }
+  }
+
+  impl core::error::Error for Error {
+  }
+
+  #[inline(always)]
+  fn padding(x: ptr<u8>, align: usize) -> usize {
+    assert!(align.is_power_of_two(), "alignment must be a power of two");
+
+    return x.addr().wrapping_neg() & align - 1;
+  }
+
+  /// A cursor that reads from a region.
+
+  #[derive(Clone)]
+  pub struct Reader {
+    begin: ptr<u8>,
+    pos: ptr<u8>,
+    end: ptr<u8>,
+  }
+
+  impl Reader {
+    /// Creates a reader over `len` bytes starting at `begin`.
+    ///
+    /// # SAFETY
+    ///
+    /// The region must be valid for reads, and its bytes initialized, for as
+    /// long as the reader is used.
+
+    #[inline(always)]
+    pub unsafe fn new(begin: ptr<u8>, len: usize) -> Reader {
+      return Reader { begin, pos: begin, end: begin + len };
+    }
+
+    /// Creates a reader over a range.
+    ///
+    /// # SAFETY
+    ///
+    /// See [`Reader::new`].
+
+    #[inline(always)]
+    pub unsafe fn from_range(x: range<u8>) -> Reader {
+      return unsafe { Reader::new(x.begin(), x.len()) };
+    }
+
+    /// The start of the region.
+
+    #[inline(always)]
+    pub fn begin(&self) -> ptr<u8> {
+      return self.begin;
+    }
+
+    /// The current position.
+
+    #[inline(always)]
+    pub fn position(&self) -> ptr<u8> {
+      return self.pos;
+    }
+
+    /// The end of the region.
+
+    #[inline(always)]
+    pub fn end(&self) -> ptr<u8> {
+      return self.end;
+    }
+
+    /// The number of bytes read or skipped so far.
+
+    #[inline(always)]
+    pub fn offset(&self) -> usize {
+      return self.pos - self.begin;
+    }
+
+    /// The number of bytes left.
+
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+      return self.end - self.pos;
+    }
+
+    /// Whether no bytes are left.
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+      return self.pos == self.end;
+    }
+
+    /// Moves the position to `x`, which must lie within the region.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` is outside the region.
+
+    #[inline(always)]
+    pub fn set_position(&mut self, x: ptr<u8>) {
+      assert!(self.begin <= x && x <= self.end, "position outside of region");
+
+      self.pos = x;
+    }
+
+    #[inline(always)]
+    fn check(&self, n: usize) -> Result<ptr<u8>, Error> {
+      let remaining = self.remaining();
+
+      if n > remaining {
+        return Err(Error::Overrun { needed: n, remaining });
+      }
+
+      return Ok(self.pos);
+    }
+
+    #[inline(always)]
+    fn advance(&mut self, n: usize) -> Result<ptr<u8>, Error> {
+      let x = self.check(n)?;
+      self.pos = x + n;
+      return Ok(x);
+    }
+
+    /// Advances past `n` bytes.
+
+    #[inline(always)]
+    pub fn skip(&mut self, n: usize) -> Result<(), Error> {
+      let _ = self.advance(n)?;
+      return Ok(());
+    }
+
+    /// Advances to the next address that is a multiple of `align`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two.
+
+    #[inline(always)]
+    pub fn align_to(&mut self, align: usize) -> Result<(), Error> {
+      return self.skip(padding(self.pos, align));
+    }
+
+    /// Reads a value in native byte order, at any alignment.
+
+    #[inline(always)]
+    pub fn read<T: Endian>(&mut self) -> Result<T, Error> {
+      let x = self.advance(size_of::<T>())?;
+      return Ok(unsafe { x.cast::<T>().read_unaligned() });
+    }
+
+    /// Reads a little-endian value, at any alignment.
+
+    #[inline(always)]
+    pub fn read_le<T: Endian>(&mut self) -> Result<T, Error> {
+      let x = self.advance(size_of::<T>())?;
+      return Ok(unsafe { x.read_le() });
+    }
+
+    /// Reads a big-endian value, at any alignment.
+
+    #[inline(always)]
+    pub fn read_be<T: Endian>(&mut self) -> Result<T, Error> {
+      let x = self.advance(size_of::<T>())?;
+      return Ok(unsafe { x.read_be() });
+    }
+
+    /// Reads a value in native byte order without advancing.
+
+    #[inline(always)]
+    pub fn peek<T: Endian>(&self) -> Result<T, Error> {
+      let x = self.check(size_of::<T>())?;
+      return Ok(unsafe { x.cast::<T>().read_unaligned() });
+    }
+
+    /// Advances past `n` bytes, returning them as a range.
+
+    #[inline(always)]
+    pub fn read_bytes(&mut self, n: usize) -> Result<range<u8>, Error> {
+      let x = self.advance(n)?;
+      return Ok(range::from_len(x, n));
+    }
+
+    /// Copies bytes into `dst`.
+
+    #[inline(always)]
+    pub fn read_into(&mut self, dst: &mut [u8]) -> Result<(), Error> {
+      let x = self.advance(dst.len())?;
+      unsafe { ptr::from(&mut *dst).copy_from_nonoverlapping(x, dst.len()) };
+      return Ok(());
+    }
+
+    /// Runs `f`, restoring the position if it fails.
+
+    #[inline(always)]
+    fn atomically<T>(&mut self, f: impl FnOnce(&mut Reader) -> Result<T, Error>) -> Result<T, Error> {
+      let start = self.pos;
+      let x = f(self);
+
+      if x.is_err() {
+        self.pos = start;
+      }
+
+      return x;
+    }
+
+    /// Reads an unsigned LEB128 varint.
+
+    pub fn read_uleb128(&mut self) -> Result<u64, Error> {
+      return self.atomically(|r| {
+        let mut value = 0u64;
+        let mut shift = 0;
+
+        loop {
+          let b = r.read::<u8>()?;
+
+          if shift == 63 && b > 1 {
+            return Err(Error::Overflow);
+          }
+
+          value |= ((b & 0x7f) as u64) << shift;
+
+          if b & 0x80 == 0 {
+            return Ok(value);
+          }
+
+          shift += 7;
+        }
+      });
+    }
+
+    /// Reads a signed LEB128 varint.
+
+    pub fn read_sleb128(&mut self) -> Result<i64, Error> {
+      return self.atomically(|r| {
+        let mut value = 0i64;
+        let mut shift = 0;
+
+        loop {
+          let b = r.read::<u8>()?;
+
+          if shift == 63 && b != 0x00 && b != 0x7f {
+            return Err(Error::Overflow);
+          }
+
+          value |= ((b & 0x7f) as i64) << shift;
+          shift += 7;
+
+          if b & 0x80 == 0 {
+            if shift < 64 && b & 0x40 != 0 {
+              value |= -1 << shift;
+            }
+
+            return Ok(value);
+          }
+        }
+      });
+    }
+
+    /// Reads a byte slice prefixed by its length as an unsigned LEB128
+    /// varint.
+
+    pub fn read_prefixed_uleb128(&mut self) -> Result<range<u8>, Error> {
+      return self.atomically(|r| {
+        let n = usize::try_from(r.read_uleb128()?).map_err(|_| Error::Overflow)?;
+        return r.read_bytes(n);
+      });
+    }
+
+    /// Reads a byte slice prefixed by its length as a little-endian `L`.
+
+    pub fn read_prefixed_le<L: Endian + TryInto<usize>>(&mut self) -> Result<range<u8>, Error> {
+      return self.atomically(|r| {
+        let n = r.read_le::<L>()?.try_into().map_err(|_| Error::Overflow)?;
+        return r.read_bytes(n);
+      });
+    }
+
+    /// Reads a byte slice prefixed by its length as a big-endian `L`.
+
+    pub fn read_prefixed_be<L: Endian + TryInto<usize>>(&mut self) -> Result<range<u8>, Error> {
+      return self.atomically(|r| {
+        let n = r.read_be::<L>()?.try_into().map_err(|_| Error::Overflow)?;
+        return r.read_bytes(n);
+      });
+    }
+  }
+
+  impl core::fmt::Debug for Reader {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return f.debug_struct("Reader").field("position", &self.pos).field("remaining", &self.remaining()).finish();
+    }
+  }
+
+  /// A cursor that writes to a region.
+
+  pub struct Writer {
+    begin: ptr<u8>,
+    pos: ptr<u8>,
+    end: ptr<u8>,
+  }
+
+  impl Writer {
+    /// Creates a writer over `len` bytes starting at `begin`.
+    ///
+    /// # SAFETY
+    ///
+    /// The region must be valid for writes for as long as the writer is
+    /// used.
+
+    #[inline(always)]
+    pub unsafe fn new(begin: ptr<u8>, len: usize) -> Writer {
+      return Writer { begin, pos: begin, end: begin + len };
+    }
+
+    /// Creates a writer over a range.
+    ///
+    /// # SAFETY
+    ///
+    /// See [`Writer::new`].
+
+    #[inline(always)]
+    pub unsafe fn from_range(x: range<u8>) -> Writer {
+      return unsafe { Writer::new(x.begin(), x.len()) };
+    }
+
+    /// The start of the region.
+
+    #[inline(always)]
+    pub fn begin(&self) -> ptr<u8> {
+      return self.begin;
+    }
+
+    /// The current position.
+
+    #[inline(always)]
+    pub fn position(&self) -> ptr<u8> {
+      return self.pos;
+    }
+
+    /// The end of the region.
+
+    #[inline(always)]
+    pub fn end(&self) -> ptr<u8> {
+      return self.end;
+    }
+
+    /// The number of bytes written or skipped so far.
+
+    #[inline(always)]
+    pub fn offset(&self) -> usize {
+      return self.pos - self.begin;
+    }
+
+    /// The number of bytes left.
+
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+      return self.end - self.pos;
+    }
+
+    /// Whether no bytes are left.
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+      return self.pos == self.end;
+    }
+
+    /// The bytes written so far.
+
+    #[inline(always)]
+    pub fn written(&self) -> range<u8> {
+      return range::new(self.begin, self.pos);
+    }
+
+    #[inline(always)]
+    fn advance(&mut self, n: usize) -> Result<ptr<u8>, Error> {
+      let remaining = self.remaining();
+
+      if n > remaining {
+        return Err(Error::Overrun { needed: n, remaining });
+      }
+
+      let x = self.pos;
+      self.pos = x + n;
+      return Ok(x);
+    }
+
+    /// Advances past `n` bytes without writing them, returning their
+    /// position so that they can be filled in later.
+
+    #[inline(always)]
+    pub fn reserve(&mut self, n: usize) -> Result<ptr<u8>, Error> {
+      return self.advance(n);
+    }
+
+    /// Advances past `n` bytes without writing them.
+
+    #[inline(always)]
+    pub fn skip(&mut self, n: usize) -> Result<(), Error> {
+      let _ = self.advance(n)?;
+      return Ok(());
+    }
+
+    /// Writes zero bytes up to the next address that is a multiple of
+    /// `align`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two.
+
+    #[inline(always)]
+    pub fn align_to(&mut self, align: usize) -> Result<(), Error> {
+      let n = padding(self.pos, align);
+      let x = self.advance(n)?;
+      unsafe { x.write_bytes(0, n) };
+      return Ok(());
+    }
+
+    /// Writes a value in native byte order, at any alignment.
+
+    #[inline(always)]
+    pub fn write<T: Endian>(&mut self, value: T) -> Result<(), Error> {
+      let x = self.advance(size_of::<T>())?;
+      unsafe { x.cast::<T>().write_unaligned(value) };
+      return Ok(());
+    }
+
+    /// Writes a value as little endian, at any alignment.
+
+    #[inline(always)]
+    pub fn write_le<T: Endian>(&mut self, value: T) -> Result<(), Error> {
+      let x = self.advance(size_of::<T>())?;
+      unsafe { x.write_le(value) };
+      return Ok(());
+    }
+
+    /// Writes a value as big endian, at any alignment.
+
+    #[inline(always)]
+    pub fn write_be<T: Endian>(&mut self, value: T) -> Result<(), Error> {
+      let x = self.advance(size_of::<T>())?;
+      unsafe { x.write_be(value) };
+      return Ok(());
+    }
+
+    /// Copies bytes from `src`.
+
+    #[inline(always)]
+    pub fn write_bytes(&mut self, src: &[u8]) -> Result<(), Error> {
+      let x = self.advance(src.len())?;
+      unsafe { x.copy_from_nonoverlapping(ptr::from(src), src.len()) };
+      return Ok(());
+    }
+
+    /// Writes an unsigned LEB128 varint.
+
+    pub fn write_uleb128(&mut self, mut value: u64) -> Result<(), Error> {
+      let mut buf = [0u8; 10];
+      let mut n = 0;
+
+      loop {
+        let b = (value & 0x7f) as u8;
+        value >>= 7;
+
+        if value == 0 {
+          buf[n] = b;
+          n += 1;
+          break;
+        }
+
+        buf[n] = b | 0x80;
+        n += 1;
+      }
+
+      return self.write_bytes(&buf[.. n]);
+    }
+
+    /// Writes a signed LEB128 varint.
+
+    pub fn write_sleb128(&mut self, mut value: i64) -> Result<(), Error> {
+      let mut buf = [0u8; 10];
+      let mut n = 0;
+
+      loop {
+        let b = (value & 0x7f) as u8;
+        value >>= 7;
+
+        if value == 0 && b & 0x40 == 0 || value == -1 && b & 0x40 != 0 {
+          buf[n] = b;
+          n += 1;
+          break;
+        }
+
+        buf[n] = b | 0x80;
+        n += 1;
+      }
+
+      return self.write_bytes(&buf[.. n]);
+    }
+
+    /// Runs `f`, restoring the position if it fails.
+
+    #[inline(always)]
+    fn atomically(&mut self, f: impl FnOnce(&mut Writer) -> Result<(), Error>) -> Result<(), Error> {
+      let start = self.pos;
+      let x = f(self);
+
+      if x.is_err() {
+        self.pos = start;
+      }
+
+      return x;
+    }
+
+    /// Writes a byte slice prefixed by its length as an unsigned LEB128
+    /// varint.
+
+    pub fn write_prefixed_uleb128(&mut self, src: &[u8]) -> Result<(), Error> {
+      return self.atomically(|w| {
+        w.write_uleb128(src.len() as u64)?;
+        return w.write_bytes(src);
+      });
+    }
+
+    /// Writes a byte slice prefixed by its length as a little-endian `L`.
+
+    pub fn write_prefixed_le<L: Endian + TryFrom<usize>>(&mut self, src: &[u8]) -> Result<(), Error> {
+      return self.atomically(|w| {
+        w.write_le(L::try_from(src.len()).map_err(|_| Error::Overflow)?)?;
+        return w.write_bytes(src);
+      });
+    }
+
+    /// Writes a byte slice prefixed by its length as a big-endian `L`.
+
+    pub fn write_prefixed_be<L: Endian + TryFrom<usize>>(&mut self, src: &[u8]) -> Result<(), Error> {
+      return self.atomically(|w| {
+        w.write_be(L::try_from(src.len()).map_err(|_| Error::Overflow)?)?;
+        return w.write_bytes(src);
+      });
+    }
+  }
+
+  impl core::fmt::Debug for Writer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return f.debug_struct("Writer").field("position", &self.pos).field("remaining", &self.remaining()).finish();
+    }
+  }
+}
+
+#[cfg(feature = "alloc")]
+pub mod global {
+  //! TODO
+
+  extern crate alloc;
+
+  use core::alloc::Layout;
+  use super::AllocError;
+  use super::Allocator;
+  use super::ptr;
+  use super::slice_layout;
+
+  /// The global allocator as an [`Allocator`].
+
+  #[derive(Clone, Copy, Debug, Default)]
+  pub struct Global;
+
+  unsafe impl Allocator for Global {
+    #[inline(always)]
+    unsafe fn alloc_layout<T>(&self, layout: Layout) -> Result<ptr<T>, AllocError> {
+      return unsafe { try_alloc_layout(layout) };
+    }
+
+    #[inline(always)]
+    unsafe fn dealloc_layout<T>(&self, x: ptr<T>, layout: Layout) {
+      unsafe { dealloc_layout(x, layout) };
+    }
+
+    #[inline(always)]
+    unsafe fn alloc_layout_zeroed<T>(&self, layout: Layout) -> Result<ptr<T>, AllocError> {
+      return unsafe { try_alloc_layout_zeroed(layout) };
+    }
+
+    #[inline(always)]
+    unsafe fn realloc_layout<T>(&self, x: ptr<T>, layout: Layout, new_size: usize) -> Result<ptr<T>, AllocError> {
+      return unsafe { try_realloc_layout(x, layout, new_size) };
+    }
+  }
+
+  /// Allocates memory with the global allocator.
+  ///
+  /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+  /// return.
+  ///
+  /// # SAFETY
+  ///
+  /// See [alloc::alloc::GlobalAlloc::alloc].
+
+  pub unsafe fn alloc_layout<T>(layout: Layout) -> ptr<T> {
+    let x = unsafe { alloc::alloc::alloc(layout) };
+    let x = ptr::from(x).cast();
+
+    if x.is_null() {
+      match alloc::alloc::handle_alloc_error(layout) {
+      }
+    }
+
+    checked::insert(x, layout);
+    return x;
+  }
+
+  /// Allocates zero-initialized memory with the global allocator.
+  ///
+  /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+  /// return.
+  ///
+  /// # SAFETY
+  ///
+  /// See [alloc::alloc::GlobalAlloc::alloc_zeroed].
+
+  pub unsafe fn alloc_layout_zeroed<T>(layout: Layout) -> ptr<T> {
+    let x = unsafe { alloc::alloc::alloc_zeroed(layout) };
+    let x = ptr::from(x).cast();
+
+    if x.is_null() {
+      match alloc::alloc::handle_alloc_error(layout) {
+      }
+    }
+
+    checked::insert(x, layout);
+    return x;
+  }
+
+  /// Allocates memory for a `T` with the global allocator.
+  ///
+  /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+  /// return.
+  ///
+  /// # SAFETY
+  ///
+  /// `T` must have non-zero size.
+
+  pub unsafe fn alloc<T>() -> ptr<T> {
+    debug_assert!(size_of::<T>() != 0);
+
+    return unsafe { alloc_layout(Layout::new::<T>()) };
+  }
+
+  /// Allocates zero-initialized memory for a `T` with the global allocator.
+  ///
+  /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+  /// return.
+  ///
+  /// # SAFETY
+  ///
+  /// `T` must have non-zero size.
+
+  pub unsafe fn alloc_zeroed<T>() -> ptr<T> {
+    debug_assert!(size_of::<T>() != 0);
+
+    return unsafe { alloc_layout_zeroed(Layout::new::<T>()) };
+  }
+
+  /// Allocates memory for a slice of `count` `T`s with the global allocator.
+  ///
+  /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+  /// return.
+  ///
+  /// # SAFETY
+  ///
+  /// - `T` must have non-zero size,
+  /// - `count` must be non-zero, and
+  /// - `count` `T`s must not overflow `Layout`.
+
+  pub unsafe fn alloc_slice<T>(count: usize) -> ptr<T> {
+    debug_assert!(size_of::<T>() != 0);
+    debug_assert!(count != 0);
+    debug_assert!(count <= isize::MAX as usize / size_of::<T>());
+
+    let align = align_of::<T>();
+    let size = count * size_of::<T>();
+    let layout = unsafe { Layout::from_size_align_unchecked(size, align) };
+    return unsafe { alloc_layout(layout) };
+  }
+
+  /// Allocates zero-initialized memory for a slice of `count` `T`s with the
+  /// global allocator.
+  ///
+  /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+  /// return.
+  ///
+  /// # SAFETY
+  ///
+  /// - `T` must have non-zero size,
+  /// - `count` must be non-zero, and
+  /// - `count` `T`s must not overflow `Layout`.
+
+  pub unsafe fn alloc_slice_zeroed<T>(count: usize) -> ptr<T> {
+    debug_assert!(size_of::<T>() != 0);
+    debug_assert!(count != 0);
+    debug_assert!(count <= isize::MAX as usize / size_of::<T>());
+
+    let align = align_of::<T>();
+    let size = count * size_of::<T>();
+    let layout = unsafe { Layout::from_size_align_unchecked(size, align) };
+    return unsafe { alloc_layout_zeroed(layout) };
+  }
+
+  /// Deallocates memory with the global allocator.
+  ///
+  /// # SAFETY
+  ///
+  /// See [alloc::alloc::GlobalAlloc::dealloc].
+
+  pub unsafe fn dealloc_layout<T>(x: ptr<T>, layout: Layout) {
+    checked::remove(x, layout);
+    unsafe { alloc::alloc::dealloc(x.cast().as_mut_ptr(), layout) };
+  }
+
+  /// Deallocates memory for a `T` with the global allocator.
+  ///
+  /// # SAFETY
+  ///
+  /// `x` must be currently allocated for a `T`.
+
+  pub unsafe fn dealloc<T>(x: ptr<T>) {
+    unsafe { dealloc_layout(x, Layout::new::<T>()) };
+  }
+
+  /// Deallocates memory for `count` `T`s with the global allocator.
+  ///
+  /// # SAFETY
+  ///
+  /// `x` must be currently allocated for a slice of `count` `T`s.
+
+  pub unsafe fn dealloc_slice<T>(x: ptr<T>, count: usize) {
+    let align = align_of::<T>();
+    let size = count * size_of::<T>();
+    let layout = unsafe { Layout::from_size_align_unchecked(size, align) };
+    unsafe { dealloc_layout(x, layout) };
+  }
+
+  /// Reallocates memory with the global allocator.
+  ///
+  /// On failure, does not alter the old block, calls
+  /// [`alloc::alloc::handle_alloc_error`], and does not return.
+  ///
+  /// # SAFETY
+  ///
+  /// See [alloc::alloc::GlobalAlloc::realloc].
+
+  pub unsafe fn realloc_layout<T>(x: ptr<T>, layout: Layout, new_size: usize) -> ptr<T> {
+    checked::check(x, layout);
+
+    let y = unsafe { alloc::alloc::realloc(x.cast().as_mut_ptr(), layout, new_size) };
+    let y = ptr::from(y).cast();
+
+    if y.is_null() {
+      match alloc::alloc::handle_alloc_error(layout) {
+      }
+    }
+
+    checked::replace(x, y, unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) });
+    return y;
+  }
+
+  /// Reallocates memory for a slice with the global allocator.
+  ///
+  /// On failure, does not alter the old block, calls
+  /// [`alloc::alloc::handle_alloc_error`], and does not return.
+  ///
+  /// # SAFETY
+  ///
+  /// - `x` must be currently allocated for a slice of `old_count` `T`s,
+  /// - `new_count` must be non-zero, and
+  /// - `new_count` `T`s must not overflow `Layout`.
+
+  pub unsafe fn realloc_slice<T>(x: ptr<T>, old_count: usize, new_count: usize) -> ptr<T> {
+    debug_assert!(new_count != 0);
+    debug_assert!(new_count <= isize::MAX as usize / size_of::<T>());
+
+    let align = align_of::<T>();
+    let old_size = old_count * size_of::<T>();
+    let new_size = new_count * size_of::<T>();
+    let layout = unsafe { Layout::from_size_align_unchecked(old_size, align) };
+    return unsafe { realloc_layout(x, layout, new_size) };
+  }
+
+  /// Allocates memory with the global allocator.
+  ///
+  /// On failure, returns an error.
+  ///
+  /// # SAFETY
+  ///
+  /// See [alloc::alloc::GlobalAlloc::alloc].
+
+  pub unsafe fn try_alloc_layout<T>(layout: Layout) -> Result<ptr<T>, AllocError> {
+    let x = unsafe { alloc::alloc::alloc(layout) };
+    let x = ptr::from(x).cast();
+
+    if x.is_null() {
+      return Err(AllocError::OutOfMemory(layout));
+    }
+
+    checked::insert(x, layout);
+    return Ok(x);
+  }
+
+  /// Allocates zero-initialized memory with the global allocator.
+  ///
+  /// On failure, returns an error.
+  ///
+  /// # SAFETY
+  ///
+  /// See [alloc::alloc::GlobalAlloc::alloc_zeroed].
+
+  pub unsafe fn try_alloc_layout_zeroed<T>(layout: Layout) -> Result<ptr<T>, AllocError> {
+    let x = unsafe { alloc::alloc::alloc_zeroed(layout) };
+    let x = ptr::from(x).cast();
+
+    if x.is_null() {
+      return Err(AllocError::OutOfMemory(layout));
+    }
+
+    checked::insert(x, layout);
+    return Ok(x);
+  }
+
+  /// Allocates memory for a `T` with the global allocator.
+  ///
+  /// On failure, returns an error.
+  ///
+  /// # SAFETY
+  ///
+  /// `T` must have non-zero size.
+
+  pub unsafe fn try_alloc<T>() -> Result<ptr<T>, AllocError> {
+    debug_assert!(size_of::<T>() != 0);
+
+    return unsafe { try_alloc_layout(Layout::new::<T>()) };
+  }
+
+  /// Allocates zero-initialized memory for a `T` with the global allocator.
+  ///
+  /// On failure, returns an error.
+  ///
+  /// # SAFETY
+  ///
+  /// `T` must have non-zero size.
+
+  pub unsafe fn try_alloc_zeroed<T>() -> Result<ptr<T>, AllocError> {
+    debug_assert!(size_of::<T>() != 0);
+
+    return unsafe { try_alloc_layout_zeroed(Layout::new::<T>()) };
+  }
+
+  /// Allocates memory for a slice of `count` `T`s with the global allocator.
+  ///
+  /// On failure, including when `count` `T`s would overflow `Layout`, returns
+  /// an error.
+  ///
+  /// # SAFETY
+  ///
+  /// - `T` must have non-zero size, and
+  /// - `count` must be non-zero.
+
+  pub unsafe fn try_alloc_slice<T>(count: usize) -> Result<ptr<T>, AllocError> {
+    debug_assert!(size_of::<T>() != 0);
+    debug_assert!(count != 0);
+
+    return unsafe { try_alloc_layout(slice_layout::<T>(count)?) };
+  }
+
+  /// Allocates zero-initialized memory for a slice of `count` `T`s with the
+  /// global allocator.
+  ///
+  /// On failure, including when `count` `T`s would overflow `Layout`, returns
+  /// an error.
+  ///
+  /// # SAFETY
+  ///
+  /// - `T` must have non-zero size, and
+  /// - `count` must be non-zero.
+
+  pub unsafe fn try_alloc_slice_zeroed<T>(count: usize) -> Result<ptr<T>, AllocError> {
+    debug_assert!(size_of::<T>() != 0);
+    debug_assert!(count != 0);
+
+    return unsafe { try_alloc_layout_zeroed(slice_layout::<T>(count)?) };
+  }
+
+  /// Reallocates memory with the global allocator.
+  ///
+  /// On failure, does not alter the old block and returns an error carrying
+  /// the requested layout.
+  ///
+  /// # SAFETY
+  ///
+  /// See [alloc::alloc::GlobalAlloc::realloc].
+
+  pub unsafe fn try_realloc_layout<T>(x: ptr<T>, layout: Layout, new_size: usize) -> Result<ptr<T>, AllocError> {
+    checked::check(x, layout);
+
+    let y = unsafe { alloc::alloc::realloc(x.cast().as_mut_ptr(), layout, new_size) };
+    let y = ptr::from(y).cast();
+    let new_layout = unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) };
+
+    if y.is_null() {
+      return Err(AllocError::OutOfMemory(new_layout));
+    }
+
+    checked::replace(x, y, new_layout);
+    return Ok(y);
+  }
+
+  /// Reallocates memory for a slice with the global allocator.
+  ///
+  /// On failure, including when `new_count` `T`s would overflow `Layout`,
+  /// does not alter the old block and returns an error.
+  ///
+  /// # SAFETY
+  ///
+  /// - `x` must be currently allocated for a slice of `old_count` `T`s, and
+  /// - `new_count` must be non-zero.
+
+  pub unsafe fn try_realloc_slice<T>(x: ptr<T>, old_count: usize, new_count: usize) -> Result<ptr<T>, AllocError> {
+    debug_assert!(new_count != 0);
+
+    let align = align_of::<T>();
+    let old_size = old_count * size_of::<T>();
+    let layout = unsafe { Layout::from_size_align_unchecked(old_size, align) };
+    let new_layout = slice_layout::<T>(new_count)?;
+    return unsafe { try_realloc_layout(x, layout, new_layout.size()) };
+  }
+
+  /// Diverges on an allocation error, calling
+  /// [`alloc::alloc::handle_alloc_error`] when there is a layout.
+
+  #[cold]
+  pub(crate) fn handle_alloc_error(e: AllocError) -> ! {
+    match e {
+      AllocError::OutOfMemory(layout) =>
+        match alloc::alloc::handle_alloc_error(layout) {
+        },
+      AllocError::CapacityOverflow =>
+        panic!("capacity overflow"),
+    }
+  }
+
+  /// Bookkeeping for the `checked` feature.
+  ///
+  /// With the feature enabled, every block allocated through this module is
+  /// recorded with its layout in a side table, and every deallocation or
+  /// reallocation is checked against it. Without the feature, the checks are
+  /// empty and compile to nothing.
+  ///
+  /// Only the most recent `FREED` frees are remembered, so the memory
+  /// used does not grow with the number of distinct addresses freed.
+  /// A pointer freed longer ago than that is reported as an unknown pointer
+  /// rather than a double free. Conversely, an address freed here and then
+  /// handed out again by another allocator is reported as a double free if
+  /// it is passed back to this module, because the tables cannot see
+  /// allocations made elsewhere.
+
+  #[cfg(feature = "checked")]
+  mod checked {
+    extern crate alloc;
+
+    use alloc::collections::BTreeMap;
+    use core::alloc::Layout;
+    use core::cell::UnsafeCell;
+    use core::sync::atomic::AtomicBool;
+    use core::sync::atomic::Ordering;
+    use crate::ptr;
+
+    /// The number of recent frees remembered for double-free reports.
+
+    pub(super) const FREED: usize = 64;
+
+    struct Tables {
+      live: BTreeMap<usize, Layout>,
+      freed: [Option<(usize, Layout)>; FREED],
+      next: usize,
+    }
+
+    impl Tables {
+      fn freed(&self, x: usize) -> Option<Layout> {
+        return self.freed.iter().flatten().find(|e| e.0 == x).map(|e| e.1);
+      }
+
+      fn forget(&mut self, x: usize) {
+        for e in self.freed.iter_mut() {
+          if let Some((y, _)) = *e && y == x {
+            *e = None;
+          }
+        }
+      }
+
+      fn record(&mut self, x: usize, layout: Layout) {
+        self.forget(x);
+        self.freed[self.next] = Some((x, layout));
+        self.next = (self.next + 1) % FREED;
+      }
+    }
+
+    struct Lock {
+      locked: AtomicBool,
+      tables: UnsafeCell<Tables>,
+    }
+
+    unsafe impl Sync for Lock {
+    }
+
+    static LOCK: Lock = Lock {
+      locked: AtomicBool::new(false),
+      tables: UnsafeCell::new(Tables { live: BTreeMap::new(), freed: [None; FREED], next: 0 }),
+    };
+
+    /// Runs `f` on the tables while holding the lock. The lock is released
+    /// before any panic is raised, so `f` reports errors by returning them.
+
+    fn with_tables<R>(f: impl FnOnce(&mut Tables) -> R) -> R {
+      while LOCK.locked.swap(true, Ordering::Acquire) {
+        while LOCK.locked.load(Ordering::Relaxed) {
+          core::hint::spin_loop();
+        }
+      }
+
+      let r = f(unsafe { &mut *LOCK.tables.get() });
+      LOCK.locked.store(false, Ordering::Release);
+      return r;
+    }
+
+    enum Fault {
+      DoubleFree(Layout),
+      Unknown,
+      Mismatch(Layout),
+    }
+
+    fn lookup(tables: &Tables, x: usize, layout: Layout) -> Result<(), Fault> {
+      return match tables.live.get(&x) {
+        Some(&live) if live == layout => Ok(()),
+        Some(&live) => Err(Fault::Mismatch(live)),
+        None => match tables.freed(x) {
+          Some(freed) => Err(Fault::DoubleFree(freed)),
+          None => Err(Fault::Unknown),
+        },
+      };
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn fail(what: &str, x: usize, layout: Layout, fault: Fault) -> ! {
+      match fault {
+        Fault::DoubleFree(freed) =>
+          panic!(
+            "pop::global: double free: {} {:#x} with size {} and alignment {}, which was already freed with size {} and alignment {}",
+            what, x, layout.size(), layout.align(), freed.size(), freed.align()
+          ),
+        Fault::Unknown =>
+          panic!(
+            "pop::global: unknown pointer: {} {:#x} with size {} and alignment {}, which was not allocated by pop::global",
+            what, x, layout.size(), layout.align()
+          ),
+        Fault::Mismatch(live) =>
+          panic!(
+            "pop::global: layout mismatch: {} {:#x} with size {} and alignment {}, which was allocated with size {} and alignment {}",
+            what, x, layout.size(), layout.align(), live.size(), live.align()
+          ),
+      }
+    }
+
+    pub(super) fn insert<T>(x: ptr<T>, layout: Layout) {
+      let x = x.addr();
+
+      with_tables(|t| {
+        t.forget(x);
+        let _ = t.live.insert(x, layout);
+      });
+    }
+
+    pub(super) fn remove<T>(x: ptr<T>, layout: Layout) {
+      let x = x.addr();
+
+      let r = with_tables(|t| {
+        lookup(t, x, layout)?;
+        let _ = t.live.remove(&x);
+        t.record(x, layout);
+        return Ok(());
+      });
+
+      if let Err(fault) = r {
+        fail("deallocating", x, layout, fault);
+      }
+    }
+
+    pub(super) fn check<T>(x: ptr<T>, layout: Layout) {
+      let x = x.addr();
+
+      if let Err(fault) = with_tables(|t| lookup(t, x, layout)) {
+        fail("reallocating", x, layout, fault);
+      }
+    }
+
+    pub(super) fn replace<T>(x: ptr<T>, y: ptr<T>, layout: Layout) {
+      let (x, y) = (x.addr(), y.addr());
+
+      with_tables(|t| {
+        if let Some(old) = t.live.remove(&x) && x != y {
+          t.record(x, old);
+        }
+
+        t.forget(y);
+        let _ = t.live.insert(y, layout);
+      });
+    }
+  }
+
+  #[cfg(not(feature = "checked"))]
+  mod checked {
+    use core::alloc::Layout;
+    use crate::ptr;
+
+    #[inline(always)]
+    pub(super) fn insert<T>(_: ptr<T>, _: Layout) {
+    }
+
+    #[inline(always)]
+    pub(super) fn remove<T>(_: ptr<T>, _: Layout) {
+    }
+
+    #[inline(always)]
+    pub(super) fn check<T>(_: ptr<T>, _: Layout) {
+    }
+
+    #[inline(always)]
+    pub(super) fn replace<T>(_: ptr<T>, _: ptr<T>, _: Layout) {
+    }
+  }
+}
+
+#[cfg(feature = "alloc")]
+pub mod arena {
+  //! Bump allocation.
+
+  use core::alloc::Layout;
+  use core::cell::Cell;
+  use super::AllocError;
+  use super::Allocator;
+  use super::global;
+  use super::ptr;
+
+  const MIN_CHUNK_SIZE: usize = 4096;
+
+  struct Chunk {
+    next: ptr<Chunk>,
+    layout: Layout,
+  }
+
+  impl Chunk {
+    #[inline(always)]
+    fn start(x: ptr<Chunk>) -> ptr<u8> {
+      return x.byte_add(size_of::<Chunk>());
+    }
+
+    #[inline(always)]
+    fn limit(x: ptr<Chunk>) -> ptr<u8> {
+      return x.byte_add(unsafe { x.as_ref() }.layout.size());
+    }
+  }
+
+  /// A bump allocator.
+  ///
+  /// Memory is carved from chunks obtained from [`global`] by advancing a
+  /// cursor. Individual allocations are never freed. Instead, the whole arena
+  /// is rolled back with [`Bump::reset`] or [`Bump::reset_to`], which keep the
+  /// chunks for reuse, or released when the `Bump` is dropped.
+  ///
+  /// Each new chunk is at least twice the size of the previous one.
+
+  pub struct Bump {
+    first: Cell<ptr<Chunk>>,
+    chunk: Cell<ptr<Chunk>>,
+    cursor: Cell<ptr<u8>>,
+    limit: Cell<ptr<u8>>,
+  }
+
+  /// A position in a [`Bump`], for use with [`Bump::reset_to`].
+
+  #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+  pub struct Mark {
+    chunk: ptr<Chunk>,
+    cursor: ptr<u8>,
+  }
+
+  impl Bump {
+    /// Creates an empty arena. Does not allocate.
+
+    #[inline(always)]
+    pub const fn new() -> Bump {
+      return Bump {
+        first: Cell::new(ptr::NULL),
+        chunk: Cell::new(ptr::NULL),
+        cursor: Cell::new(ptr::NULL),
+        limit: Cell::new(ptr::NULL),
+      };
+    }
+
+    /// Allocates memory.
+    ///
+    /// A zero-sized request returns a dangling, aligned pointer.
+    ///
+    /// On failure, returns an error.
+
+    #[inline(always)]
+    pub fn try_alloc_layout<T>(&self, layout: Layout) -> Result<ptr<T>, AllocError> {
+      if layout.size() == 0 {
+        return Ok(ptr::invalid(layout.align()));
+      }
+
+      let cursor = self.cursor.get();
+      let limit = self.limit.get();
+      let pad = cursor.addr().wrapping_neg() & layout.align() - 1;
+      let room = limit.byte_diff(cursor);
+
+      if pad > room || layout.size() > room - pad {
+        return self.alloc_slow(layout);
+      }
+
+      let x = cursor.byte_add(pad);
+      self.cursor.set(x.byte_add(layout.size()));
+      return Ok(x);
+    }
+
+    /// Allocates memory.
+    ///
+    /// A zero-sized request returns a dangling, aligned pointer.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+
+    #[inline(always)]
+    pub fn alloc_layout<T>(&self, layout: Layout) -> ptr<T> {
+      match self.try_alloc_layout(layout) {
+        Ok(x) => return x,
+        Err(e) => global::handle_alloc_error(e),
+      }
+    }
+
+    /// Allocates memory for a `T`.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+
+    #[inline(always)]
+    pub fn alloc<T>(&self) -> ptr<T> {
+      return self.alloc_layout(Layout::new::<T>());
+    }
+
+    /// Allocates memory for a slice of `count` `T`s.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return. Panics if `count` `T`s would overflow `Layout`.
+
+    #[inline(always)]
+    pub fn alloc_slice<T>(&self, count: usize) -> ptr<T> {
+      match super::slice_layout::<T>(count) {
+        Ok(layout) => return self.alloc_layout(layout),
+        Err(e) => global::handle_alloc_error(e),
+      }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn alloc_slow<T>(&self, layout: Layout) -> Result<ptr<T>, AllocError> {
+      // Look for a retained chunk, left over from a reset, that fits.
+
+      let mut last = self.chunk.get();
+      let mut chunk = if last.is_null() { self.first.get() } else { unsafe { last.as_ref() }.next };
+
+      while ! chunk.is_null() {
+        let start = Chunk::start(chunk);
+        let limit = Chunk::limit(chunk);
+        let pad = start.addr().wrapping_neg() & layout.align() - 1;
+        let room = limit.byte_diff(start);
+
+        if pad <= room && layout.size() <= room - pad {
+          let x = start.byte_add(pad);
+          self.chunk.set(chunk);
+          self.cursor.set(x.byte_add(layout.size()));
+          self.limit.set(limit);
+          return Ok(x);
+        }
+
+        last = chunk;
+        chunk = unsafe { chunk.as_ref() }.next;
+      }
+
+      // Otherwise, append a new chunk.
+
+      let min_size =
+        size_of::<Chunk>()
+          .checked_add(layout.align() - 1)
+          .and_then(|n| n.checked_add(layout.size()))
+          .ok_or(AllocError::CapacityOverflow)?;
+
+      let old_size = if last.is_null() { MIN_CHUNK_SIZE / 2 } else { unsafe { last.as_ref() }.layout.size() };
+      let size = old_size.saturating_mul(2).max(min_size);
+      let align = align_of::<Chunk>().max(layout.align());
+      let chunk_layout = Layout::from_size_align(size, align).map_err(|_| AllocError::CapacityOverflow)?;
+      let chunk = unsafe { global::try_alloc_layout::<Chunk>(chunk_layout) }?;
+
+      unsafe { chunk.write(Chunk { next: ptr::NULL, layout: chunk_layout }) };
+
+      if last.is_null() {
+        self.first.set(chunk);
+      } else {
+        unsafe { last.as_mut_ref() }.next = chunk;
+      }
+
+      let start = Chunk::start(chunk);
+      let pad = start.addr().wrapping_neg() & layout.align() - 1;
+      let x = start.byte_add(pad);
+      self.chunk.set(chunk);
+      self.cursor.set(x.byte_add(layout.size()));
+      self.limit.set(Chunk::limit(chunk));
+      return Ok(x);
+    }
+
+    /// The current position, for a later [`Bump::reset_to`].
+
+    #[inline(always)]
+    pub fn mark(&self) -> Mark {
+      return Mark { chunk: self.chunk.get(), cursor: self.cursor.get() };
+    }
+
+    /// Rolls back every allocation made since `mark` was taken, keeping all
+    /// chunks for reuse.
+    ///
+    /// # SAFETY
+    ///
+    /// `mark` must have been returned by [`Bump::mark`] on this arena.
+
+    #[inline(always)]
+    pub unsafe fn reset_to(&self, mark: Mark) {
+      self.chunk.set(mark.chunk);
+      self.cursor.set(mark.cursor);
+      self.limit.set(if mark.chunk.is_null() { ptr::NULL } else { Chunk::limit(mark.chunk) });
+    }
+
+    /// Rolls back every allocation, keeping all chunks for reuse.
+
+    pub fn reset(&self) {
+      let first = self.first.get();
+
+      if first.is_null() {
+        return;
+      }
+
+      self.chunk.set(first);
+      self.cursor.set(Chunk::start(first));
+      self.limit.set(Chunk::limit(first));
+    }
+
+    /// The total size in bytes of the chunks held by the arena.
+
+    pub fn capacity(&self) -> usize {
+      let mut n = 0;
+      let mut chunk = self.first.get();
+
+      while ! chunk.is_null() {
+        let c = unsafe { chunk.as_ref() };
+        n += c.layout.size();
+        chunk = c.next;
+      }
+
+      return n;
+    }
+  }
+
+  impl Default for Bump {
+    #[inline(always)]
+    fn default() -> Bump {
+      return Bump::new();
+    }
+  }
+
+  impl Drop for Bump {
+    fn drop(&mut self) {
+      let mut chunk = self.first.get();
+
+      while ! chunk.is_null() {
+        let Chunk { next, layout } = unsafe { chunk.read() };
+        unsafe { global::dealloc_layout(chunk, layout) };
+        chunk = next;
+      }
+    }
+  }
+
+  impl core::fmt::Debug for Bump {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return f.debug_struct("Bump").field("cursor", &self.cursor.get()).field("limit", &self.limit.get()).finish();
+    }
+  }
+
+  /// Deallocation is a no-op. Reallocating the most recent allocation extends
+  /// it in place when there is room.
+
+  unsafe impl Allocator for Bump {
+    #[inline(always)]
+    unsafe fn alloc_layout<T>(&self, layout: Layout) -> Result<ptr<T>, AllocError> {
+      return self.try_alloc_layout(layout);
+    }
+
+    #[inline(always)]
+    unsafe fn dealloc_layout<T>(&self, _: ptr<T>, _: Layout) {
+    }
+
+    unsafe fn realloc_layout<T>(&self, x: ptr<T>, layout: Layout, new_size: usize) -> Result<ptr<T>, AllocError> {
+      let cursor = self.cursor.get();
+
+      if x.byte_add::<u8>(layout.size()) == cursor && new_size <= self.limit.get().byte_diff(x) {
+        self.cursor.set(x.byte_add(new_size));
+        return Ok(x);
+      }
+
+      let new_layout = unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) };
+      let y = self.try_alloc_layout::<u8>(new_layout)?;
+      unsafe { y.copy_from_nonoverlapping(x.cast(), layout.size().min(new_size)) };
+      return Ok(y.cast());
+    }
+  }
+}
+
+#[cfg(feature = "alloc")]
+pub mod pool {
+  //! Fixed-size slab allocation.
+
+  use core::alloc::Layout;
+  use core::marker::PhantomData;
+  use super::global;
+  use super::ptr;
+
+  const MIN_PAGE_SIZE: usize = 4096;
+
+  struct Page<T> {
+    prev: ptr<Page<T>>,
+    next: ptr<Page<T>>,
+    free: ptr<T>,
+    used: usize,
+    fresh: usize,
+  }
+
+  /// A pool of slots for single `T`s.
+  ///
+  /// Slots are carved from power-of-two-aligned pages obtained from
+  /// [`global`]. Freed slots are threaded into a per-page free list stored in
+  /// the slots themselves, so both [`Pool::alloc`] and [`Pool::free`] are
+  /// O(1). A page is returned to the global allocator as soon as all of its
+  /// slots are free.
+  ///
+  /// Pages are kept in one list, with pages that have a free slot in front of
+  /// pages that are full.
+  ///
+  /// The pool hands out uninitialized memory and never drops a `T`. It is
+  /// `Send` and `Sync` only if `T` is.
+  ///
+  /// ```compile_fail
+  /// fn is_send<T: Send>() {}
+  ///
+  /// is_send::<pop::pool::Pool<std::rc::Rc<u8>>>();
+  /// ```
+
+  pub struct Pool<T> {
+    head: ptr<Page<T>>,
+    tail: ptr<Page<T>>,
+    pages: usize,
+    live: usize,
+    _phantom_data: PhantomData<fn(T) -> T>,
+  }
+
+  unsafe impl<T: Send> Send for Pool<T> {
+  }
+
+  unsafe impl<T: Sync> Sync for Pool<T> {
+  }
+
+  /// Occupancy statistics for a [`Pool`].
+
+  #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+  pub struct Stats {
+    /// The number of pages held.
+    pub pages: usize,
+    /// The number of slots per page.
+    pub slots_per_page: usize,
+    /// The number of slots currently allocated.
+    pub live: usize,
+  }
+
+  impl Stats {
+    /// The total number of slots in all held pages.
+
+    #[inline(always)]
+    pub fn capacity(self) -> usize {
+      return self.pages * self.slots_per_page;
+    }
+  }
+
+  impl<T> Pool<T> {
+    const SLOT_ALIGN: usize = max(align_of::<T>(), align_of::<ptr<T>>());
+
+    const SLOT_SIZE: usize = max(size_of::<T>(), size_of::<ptr<T>>()).next_multiple_of(Self::SLOT_ALIGN);
+
+    const SLOT_OFFSET: usize = size_of::<Page<T>>().next_multiple_of(Self::SLOT_ALIGN);
+
+    const PAGE_SIZE: usize = max(MIN_PAGE_SIZE, Self::SLOT_OFFSET + 8 * Self::SLOT_SIZE).next_power_of_two();
+
+    const SLOTS_PER_PAGE: usize = (Self::PAGE_SIZE - Self::SLOT_OFFSET) / Self::SLOT_SIZE;
+
+    const PAGE_LAYOUT: Layout =
+      match Layout::from_size_align(Self::PAGE_SIZE, Self::PAGE_SIZE) {
+        Ok(layout) => layout,
+        Err(_) => panic!("pool page size overflows layout"),
+      };
+
+    /// Creates an empty pool. Does not allocate.
+
+    #[inline(always)]
+    pub const fn new() -> Pool<T> {
+      return Pool { head: ptr::NULL, tail: ptr::NULL, pages: 0, live: 0, _phantom_data: PhantomData };
+    }
+
+    /// Allocates a slot for a `T`.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+
+    pub fn alloc(&mut self) -> ptr<T> {
+      let mut page = self.head;
+
+      if page.is_null() || unsafe { page.as_ref() }.used == Self::SLOTS_PER_PAGE {
+        page = unsafe { global::alloc_layout::<Page<T>>(Self::PAGE_LAYOUT) };
+        unsafe { page.write(Page { prev: ptr::NULL, next: ptr::NULL, free: ptr::NULL, used: 0, fresh: 0 }) };
+        self.push_front(page);
+        self.pages += 1;
+      }
+
+      let p = unsafe { page.as_mut_ref() };
+
+      let x =
+        if ! p.free.is_null() {
+          let x = p.free;
+          p.free = unsafe { x.cast::<ptr<T>>().read() };
+          x
+        } else {
+          let x = page.byte_add(Self::SLOT_OFFSET + p.fresh * Self::SLOT_SIZE);
+          p.fresh += 1;
+          x
+        };
+
+      p.used += 1;
+      self.live += 1;
+
+      if p.used == Self::SLOTS_PER_PAGE {
+        self.unlink(page);
+        self.push_back(page);
+      }
+
+      return x;
+    }
+
+    /// Frees a slot.
+    ///
+    /// # SAFETY
+    ///
+    /// `x` must have been returned by [`Pool::alloc`] on this pool and not
+    /// freed since.
+
+    pub unsafe fn free(&mut self, x: ptr<T>) {
+      let page = x.with_addr(x.addr() & ! (Self::PAGE_SIZE - 1)).cast::<Page<T>>();
+      let p = unsafe { page.as_mut_ref() };
+      let was_full = p.used == Self::SLOTS_PER_PAGE;
+
+      unsafe { x.cast::<ptr<T>>().write(p.free) };
+      p.free = x;
+      p.used -= 1;
+      self.live -= 1;
+
+      if p.used == 0 {
+        self.unlink(page);
+        self.pages -= 1;
+        unsafe { global::dealloc_layout(page, Self::PAGE_LAYOUT) };
+      } else if was_full {
+        self.unlink(page);
+        self.push_front(page);
+      }
+    }
+
+    /// Occupancy statistics.
+
+    #[inline(always)]
+    pub fn stats(&self) -> Stats {
+      return Stats { pages: self.pages, slots_per_page: Self::SLOTS_PER_PAGE, live: self.live };
+    }
+
+    fn push_front(&mut self, page: ptr<Page<T>>) {
+      let p = unsafe { page.as_mut_ref() };
+      p.prev = ptr::NULL;
+      p.next = self.head;
+
+      if self.head.is_null() {
+        self.tail = page;
+      } else {
+        unsafe { self.head.as_mut_ref() }.prev = page;
+      }
+
+      self.head = page;
+    }
+
+    fn push_back(&mut self, page: ptr<Page<T>>) {
+      let p = unsafe { page.as_mut_ref() };
+      p.prev = self.tail;
+      p.next = ptr::NULL;
+
+      if self.tail.is_null() {
+        self.head = page;
+      } else {
+        unsafe { self.tail.as_mut_ref() }.next = page;
+      }
+
+      self.tail = page;
+    }
+
+    fn unlink(&mut self, page: ptr<Page<T>>) {
+      let Page { prev, next, .. } = *unsafe { page.as_ref() };
+
+      if prev.is_null() {
+        self.head = next;
+      } else {
+        unsafe { prev.as_mut_ref() }.next = next;
+      }
+
+      if next.is_null() {
+        self.tail = prev;
+      } else {
+        unsafe { next.as_mut_ref() }.prev = prev;
+      }
+    }
+  }
+
+  impl<T> Default for Pool<T> {
+    #[inline(always)]
+    fn default() -> Pool<T> {
+      return Pool::new();
+    }
+  }
+
+  impl<T> Drop for Pool<T> {
+    fn drop(&mut self) {
+      let mut page = self.head;
+
+      while ! page.is_null() {
+        let next = unsafe { page.as_ref() }.next;
+        unsafe { global::dealloc_layout(page, Self::PAGE_LAYOUT) };
+        page = next;
+      }
+    }
+  }
+
+  impl<T> core::fmt::Debug for Pool<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return f.debug_struct("Pool").field("stats", &self.stats()).finish();
+    }
+  }
+
+  const fn max(a: usize, b: usize) -> usize {
+    return if a >= b { a } else { b };
+  }
+}
+
+#[cfg(feature = "alloc")]
+pub mod compressed {
+  //! Compressed pointers.
+  //!
+  //! A [`Cage`] is a contiguous region of at most 4 GiB. A [`cptr32`] is a
+  //! 32-bit offset into a cage, which is half the size of a full pointer on
+  //! 64-bit targets. Offset zero is reserved for null.
+
+  use core::alloc::Layout;
+  use core::cell::Cell;
+  use core::marker::PhantomData;
+  use super::AllocError;
+  use super::Allocator;
+  use super::global;
+  use super::ptr;
+
+  /// The granularity at which memory is committed, which is also the
+  /// alignment of the base. This is a multiple of the page size on every
+  /// supported target.
+
+  const GRANULE: usize = 1 << 16;
+
+  /// A pointer stored as a 32-bit offset from the base of a [`Cage`].
+
+  #[allow(non_camel_case_types)]
+  #[repr(transparent)]
+  pub struct cptr32<T>(u32, PhantomData<fn(T) -> T>);
+
+  impl<T> Clone for cptr32<T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+      return *self;
+    }
+  }
+
+  impl<T> Copy for cptr32<T> {
+  }
+
+  impl<T> Eq for cptr32<T> {
+  }
+
+  impl<T> core::hash::Hash for cptr32<T> {
+    #[inline(always)]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+      self.0.hash(state);
+    }
+  }
+
+  impl<T> Ord for cptr32<T> {
+    #[inline(always)]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+      return self.0.cmp(&other.0);
+    }
+  }
+
+  impl<T> PartialEq for cptr32<T> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+      return self.0 == other.0;
+    }
+  }
+
+  impl<T> PartialOrd for cptr32<T> {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+      return self.0.partial_cmp(&other.0);
+    }
+  }
+
+  impl<T> cptr32<T> {
+    /// The null pointer, at offset zero.
+
+    pub const NULL: cptr32<T> = cptr32(0, PhantomData);
+
+    /// Creates a pointer with the given offset.
+
+    #[inline(always)]
+    pub const fn from_offset(offset: u32) -> cptr32<T> {
+      return cptr32(offset, PhantomData);
+    }
+
+    /// The offset of the pointer from the cage base.
+
+    #[inline(always)]
+    pub const fn offset(self) -> u32 {
+      return self.0;
+    }
+
+    /// Whether the offset is zero.
+
+    #[inline(always)]
+    pub const fn is_null(self) -> bool {
+      return self.0 == 0;
+    }
+
+    /// Casts the pointer to a different type.
+
+    #[inline(always)]
+    pub const fn cast<U>(self) -> cptr32<U> {
+      return cptr32(self.0, PhantomData);
+    }
+
+    /// Compresses a pointer into the cage at `base`. Null maps to null.
+    ///
+    /// The pointer must be null or point into the cage. This is checked with
+    /// `debug_assert!`.
+
+    #[inline(always)]
+    pub fn compress(x: ptr<T>, base: ptr<u8>) -> cptr32<T> {
+      if x.is_null() {
+        return cptr32::NULL;
+      }
+
+      let offset = x.byte_diff(base);
+      debug_assert!(offset != 0 && offset <= u32::MAX as usize);
+      return cptr32(offset as u32, PhantomData);
+    }
+
+    /// Decompresses a pointer into the cage at `base`. Null maps to null.
+
+    #[inline(always)]
+    pub fn decompress(self, base: ptr<u8>) -> ptr<T> {
+      if self.is_null() {
+        return ptr::NULL;
+      }
+
+      return base.byte_add(self.0 as usize);
+    }
+  }
+
+  impl<T> Default for cptr32<T> {
+    #[inline(always)]
+    fn default() -> cptr32<T> {
+      return cptr32::NULL;
+    }
+  }
+
+  impl<T> core::fmt::Debug for cptr32<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return write!(f, "cptr32({:#x})", self.0);
+    }
+  }
+
+  /// A contiguous region of at most 4 GiB, with a bump allocator that hands
+  /// out [`cptr32`]s into it.
+  ///
+  /// With the `reserve` feature on Unix and Windows, the cage reserves
+  /// address space for the whole region up front and commits memory in
+  /// 64 KiB steps as the bump pointer advances. Otherwise, the whole region
+  /// is allocated from [`global`] up front and is committed from the start.
+  ///
+  /// Individual allocations are never freed. Instead, the whole cage is
+  /// rolled back with [`Cage::reset`] or released when the `Cage` is dropped.
+  /// Memory that has been committed stays committed until then.
+
+  pub struct Cage {
+    base: ptr<u8>,
+    size: usize,
+    cursor: Cell<usize>,
+    committed: Cell<usize>,
+  }
+
+  impl Cage {
+    /// The largest supported size, 4 GiB.
+
+    pub const MAX_SIZE: u64 = 1 << 32;
+
+    /// Reserves a cage of `size` bytes.
+    ///
+    /// On failure, returns an error. Panics if `size` is zero or greater
+    /// than [`Cage::MAX_SIZE`].
+
+    pub fn try_new(size: usize) -> Result<Cage, AllocError> {
+      assert!(size != 0 && size as u64 <= Cage::MAX_SIZE, "invalid cage size");
+
+      let layout = Layout::from_size_align(size.next_multiple_of(GRANULE), GRANULE).map_err(|_| AllocError::CapacityOverflow)?;
+      let base = region::reserve(layout)?;
+      let committed = if region::COMMITTED { layout.size() } else { 0 };
+
+      // Offset zero is null, so allocation starts past it.
+
+      return Ok(Cage { base, size, cursor: Cell::new(1), committed: Cell::new(committed) });
+    }
+
+    /// Reserves a cage of `size` bytes.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return. Panics if `size` is zero or greater than [`Cage::MAX_SIZE`].
+
+    pub fn new(size: usize) -> Cage {
+      match Cage::try_new(size) {
+        Ok(cage) => return cage,
+        Err(e) => global::handle_alloc_error(e),
+      }
+    }
+
+    /// The base of the cage.
+
+    #[inline(always)]
+    pub fn base(&self) -> ptr<u8> {
+      return self.base;
+    }
+
+    /// The size of the cage in bytes.
+
+    #[inline(always)]
+    pub fn size(&self) -> usize {
+      return self.size;
+    }
+
+    /// The number of bytes allocated so far, including the reserved null
+    /// byte and padding.
+
+    #[inline(always)]
+    pub fn used(&self) -> usize {
+      return self.cursor.get();
+    }
+
+    /// The number of bytes backed by committed memory, counted from the base.
+
+    #[inline(always)]
+    pub fn committed(&self) -> usize {
+      return self.committed.get();
+    }
+
+    /// Whether `x` points into the cage.
+
+    #[inline(always)]
+    pub fn contains<T>(&self, x: ptr<T>) -> bool {
+      return x.byte_diff(self.base) < self.size;
+    }
+
+    /// Compresses a pointer into this cage. See [`cptr32::compress`].
+
+    #[inline(always)]
+    pub fn compress<T>(&self, x: ptr<T>) -> cptr32<T> {
+      return cptr32::compress(x, self.base);
+    }
+
+    /// Decompresses a pointer into this cage. See [`cptr32::decompress`].
+
+    #[inline(always)]
+    pub fn decompress<T>(&self, x: cptr32<T>) -> ptr<T> {
+      return x.decompress(self.base);
+    }
+
+    /// Allocates memory.
+    ///
+    /// On failure, returns an error.
+
+    #[inline(always)]
+    pub fn try_alloc_layout<T>(&self, layout: Layout) -> Result<cptr32<T>, AllocError> {
+      // The base is only page aligned, so align the address, not the offset.
+
+      let cursor = self.cursor.get();
+      let padding = (self.base.addr() + cursor).wrapping_neg() & layout.align() - 1;
+      let start = cursor.saturating_add(padding);
+
+      if start >= self.size || layout.size() > self.size - start {
+        return Err(AllocError::OutOfMemory(layout));
+      }
+
+      let end = start + layout.size();
+
+      if end > self.committed.get() {
+        self.commit(end, layout)?;
+      }
+
+      self.cursor.set(end);
+      return Ok(cptr32(start as u32, PhantomData));
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn commit(&self, end: usize, layout: Layout) -> Result<(), AllocError> {
+      let old = self.committed.get();
+      let new = end.next_multiple_of(GRANULE);
+
+      if ! unsafe { region::commit(self.base.byte_add(old), new - old) } {
+        return Err(AllocError::OutOfMemory(layout));
+      }
+
+      self.committed.set(new);
+      return Ok(());
+    }
+
+    /// Allocates memory.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+
+    #[inline(always)]
+    pub fn alloc_layout<T>(&self, layout: Layout) -> cptr32<T> {
+      match self.try_alloc_layout(layout) {
+        Ok(x) => return x,
+        Err(e) => global::handle_alloc_error(e),
+      }
+    }
+
+    /// Allocates memory for a `T`.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+
+    #[inline(always)]
+    pub fn alloc<T>(&self) -> cptr32<T> {
+      debug_assert!(size_of::<T>() != 0);
+
+      return self.alloc_layout(Layout::new::<T>());
+    }
+
+    /// Allocates memory for a slice of `count` `T`s.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+
+    #[inline(always)]
+    pub fn alloc_slice<T>(&self, count: usize) -> cptr32<T> {
+      debug_assert!(size_of::<T>() != 0);
+      debug_assert!(count != 0);
+
+      match super::slice_layout::<T>(count) {
+        Ok(layout) => return self.alloc_layout(layout),
+        Err(e) => global::handle_alloc_error(e),
+      }
+    }
+
+    /// Rolls back every allocation.
+
+    #[inline(always)]
+    pub fn reset(&self) {
+      self.cursor.set(1);
+    }
+  }
+
+  impl Drop for Cage {
+    fn drop(&mut self) {
+      let layout = unsafe { Layout::from_size_align_unchecked(self.size.next_multiple_of(GRANULE), GRANULE) };
+      unsafe { region::release(self.base, layout) };
+    }
+  }
+
+  impl core::fmt::Debug for Cage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return
+        f.debug_struct("Cage")
+          .field("base", &self.base)
+          .field("size", &self.size)
+          .field("used", &self.cursor.get())
+          .field("committed", &self.committed.get())
+          .finish();
+    }
+  }
+
+  /// Address space for a cage, reserved with `mmap` and committed with
+  /// `mprotect`.
+
+  #[cfg(all(feature = "reserve", unix))]
+  mod region {
+    use core::alloc::Layout;
+    use super::AllocError;
+    use super::ptr;
+
+    pub(super) const COMMITTED: bool = false;
+
+    pub(super) fn reserve(layout: Layout) -> Result<ptr<u8>, AllocError> {
+      let flags = libc::MAP_PRIVATE | libc::MAP_ANON;
+      let x = unsafe { libc::mmap(core::ptr::null_mut(), layout.size(), libc::PROT_NONE, flags, -1, 0) };
+
+      if x == libc::MAP_FAILED {
+        return Err(AllocError::OutOfMemory(layout));
+      }
+
+      return Ok(ptr::from(x.cast::<u8>()));
+    }
+
+    /// # SAFETY
+    ///
+    /// The bytes must lie within a reservation, starting on a page boundary.
+
+    pub(super) unsafe fn commit(x: ptr<u8>, size: usize) -> bool {
+      return unsafe { libc::mprotect(x.as_mut_ptr().cast(), size, libc::PROT_READ | libc::PROT_WRITE) } == 0;
+    }
+
+    /// # SAFETY
+    ///
+    /// The reservation must be live, and is not live afterwards.
+
+    pub(super) unsafe fn release(x: ptr<u8>, layout: Layout) {
+      let _ = unsafe { libc::munmap(x.as_mut_ptr().cast(), layout.size()) };
+    }
+  }
+
+  /// Address space for a cage, reserved and committed with `VirtualAlloc`.
+
+  #[cfg(all(feature = "reserve", windows))]
+  mod region {
+    use core::alloc::Layout;
+    use windows_sys::Win32::System::Memory;
+    use super::AllocError;
+    use super::ptr;
+
+    pub(super) const COMMITTED: bool = false;
+
+    pub(super) fn reserve(layout: Layout) -> Result<ptr<u8>, AllocError> {
+      let x = unsafe { Memory::VirtualAlloc(core::ptr::null(), layout.size(), Memory::MEM_RESERVE, Memory::PAGE_NOACCESS) };
+
+      if x.is_null() {
+        return Err(AllocError::OutOfMemory(layout));
+      }
+
+      return Ok(ptr::from(x.cast::<u8>()));
+    }
+
+    /// # SAFETY
+    ///
+    /// The bytes must lie within a reservation.
+
+    pub(super) unsafe fn commit(x: ptr<u8>, size: usize) -> bool {
+      return ! unsafe { Memory::VirtualAlloc(x.as_const_ptr().cast(), size, Memory::MEM_COMMIT, Memory::PAGE_READWRITE) }.is_null();
+    }
+
+    /// # SAFETY
+    ///
+    /// The reservation must be live, and is not live afterwards.
+
+    pub(super) unsafe fn release(x: ptr<u8>, _: Layout) {
+      let _ = unsafe { Memory::VirtualFree(x.as_mut_ptr().cast(), 0, Memory::MEM_RELEASE) };
+    }
+  }
+
+  /// A cage region allocated from [`global`], which is committed up front.
+
+  #[cfg(not(all(feature = "reserve", any(unix, windows))))]
+  mod region {
+    use core::alloc::Layout;
+    use super::AllocError;
+    use super::global;
+    use super::ptr;
+
+    pub(super) const COMMITTED: bool = true;
+
+    pub(super) fn reserve(layout: Layout) -> Result<ptr<u8>, AllocError> {
+      return unsafe { global::try_alloc_layout(layout) };
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn commit(_: ptr<u8>, _: usize) -> bool {
+      return true;
+    }
+
+    pub(super) unsafe fn release(x: ptr<u8>, layout: Layout) {
+      unsafe { global::dealloc_layout(x, layout) };
+    }
+  }
+
+  /// Deallocation is a no-op.
+
+  unsafe impl Allocator for Cage {
+    #[inline(always)]
+    unsafe fn alloc_layout<T>(&self, layout: Layout) -> Result<ptr<T>, AllocError> {
+      return self.try_alloc_layout(layout).map(|x| self.decompress(x));
+    }
+
+    #[inline(always)]
+    unsafe fn dealloc_layout<T>(&self, _: ptr<T>, _: Layout) {
+    }
+  }
+}
+
+#[cfg(feature = "alloc")]
+pub mod raw {
+  //! Raw growable arrays.
+
+  use core::marker::PhantomData;
+  use super::AllocError;
+  use super::Allocator;
+  use super::global;
+  use super::global::Global;
+  use super::ptr;
+
+  /// The buffer of a growable array: a pointer and a capacity.
+  ///
+  /// A `RawVec` owns its allocation but not its elements. It never reads,
+  /// writes, or drops elements, except in [`RawVec::insert_at`] and
+  /// [`RawVec::remove_at`]. The length is kept by the caller.
+  ///
+  /// With zero capacity or a zero-sized `T`, nothing is allocated and the
+  /// pointer is dangling but aligned. A zero-sized `T` has capacity
+  /// `usize::MAX`.
+  ///
+  /// Like `Vec<T>`, a `RawVec<T>` is `Send` and `Sync` only if `T` is.
+  ///
+  /// ```compile_fail
+  /// fn is_send<T: Send>() {}
+  ///
+  /// is_send::<pop::raw::RawVec<std::rc::Rc<u8>>>();
+  /// ```
+
+  pub struct RawVec<T, A: Allocator = Global> {
+    ptr: ptr<T>,
+    cap: usize,
+    alloc: A,
+    _phantom_data: PhantomData<T>,
+  }
+
+  unsafe impl<T: Send, A: Allocator + Send> Send for RawVec<T, A> {
+  }
+
+  unsafe impl<T: Sync, A: Allocator + Sync> Sync for RawVec<T, A> {
+  }
+
+  impl<T> RawVec<T> {
+    /// Creates an empty buffer in the global allocator. Does not allocate.
+
+    #[inline(always)]
+    pub const fn new() -> RawVec<T> {
+      return RawVec::new_in(Global);
+    }
+
+    /// Creates a buffer in the global allocator with at least the given
+    /// capacity.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+
+    #[inline(always)]
+    pub fn with_capacity(cap: usize) -> RawVec<T> {
+      return RawVec::with_capacity_in(cap, Global);
+    }
+  }
+
+  impl<T, A: Allocator> RawVec<T, A> {
+    const IS_ZST: bool = size_of::<T>() == 0;
+
+    /// Creates an empty buffer in the given allocator. Does not allocate.
+
+    #[inline(always)]
+    pub const fn new_in(alloc: A) -> RawVec<T, A> {
+      let cap = if Self::IS_ZST { usize::MAX } else { 0 };
+      return RawVec { ptr: ptr::invalid(align_of::<T>()), cap, alloc, _phantom_data: PhantomData };
+    }
+
+    /// Creates a buffer in the given allocator with at least the given
+    /// capacity.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+
+    #[inline(always)]
+    pub fn with_capacity_in(cap: usize, alloc: A) -> RawVec<T, A> {
+      let mut x = RawVec::new_in(alloc);
+      x.reserve_exact(0, cap);
+      return x;
+    }
+
+    /// The pointer to the buffer.
+
+    #[inline(always)]
+    pub fn ptr(&self) -> ptr<T> {
+      return self.ptr;
+    }
+
+    /// The capacity.
+
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+      return self.cap;
+    }
+
+    /// The allocator.
+
+    #[inline(always)]
+    pub fn allocator(&self) -> &A {
+      return &self.alloc;
+    }
+
+    fn set_capacity(&mut self, cap: usize) -> Result<(), AllocError> {
+      debug_assert!(! Self::IS_ZST);
+
+      if cap == self.cap {
+        return Ok(());
+      }
+
+      if cap == 0 {
+        unsafe { self.alloc.dealloc_slice(self.ptr, self.cap) };
+        self.ptr = ptr::invalid(align_of::<T>());
+      } else if self.cap == 0 {
+        self.ptr = unsafe { self.alloc.alloc_slice(cap) }?;
+      } else {
+        self.ptr = unsafe { self.alloc.realloc_slice(self.ptr, self.cap, cap) }?;
+      }
+
+      self.cap = cap;
+      return Ok(());
+    }
+
+    /// Grows the capacity to at least `len + additional`, at least doubling
+    /// it.
+    ///
+    /// On failure, including when the capacity would overflow, returns an
+    /// error and leaves the buffer unchanged.
+
+    #[cold]
+    #[inline(never)]
+    pub fn grow_amortized(&mut self, len: usize, additional: usize) -> Result<(), AllocError> {
+      let required = len.checked_add(additional).ok_or(AllocError::CapacityOverflow)?;
+
+      if required <= self.cap {
+        return Ok(());
+      }
+
+      let min_cap = if size_of::<T>() == 1 { 8 } else if size_of::<T>() <= 1024 { 4 } else { 1 };
+      let cap = required.max(self.cap.saturating_mul(2)).max(min_cap);
+      return self.set_capacity(cap);
+    }
+
+    /// Ensures capacity for at least `len + additional` elements, growing
+    /// geometrically. `len` must not exceed the capacity.
+    ///
+    /// On failure, returns an error and leaves the buffer unchanged.
+
+    #[inline(always)]
+    pub fn try_reserve(&mut self, len: usize, additional: usize) -> Result<(), AllocError> {
+      if additional <= self.cap.wrapping_sub(len) {
+        return Ok(());
+      }
+
+      return self.grow_amortized(len, additional);
+    }
+
+    /// Ensures capacity for exactly `len + additional` elements, if it must
+    /// grow. `len` must not exceed the capacity.
+    ///
+    /// On failure, returns an error and leaves the buffer unchanged.
+
+    pub fn try_reserve_exact(&mut self, len: usize, additional: usize) -> Result<(), AllocError> {
+      if additional <= self.cap.wrapping_sub(len) {
+        return Ok(());
+      }
+
+      let cap = len.checked_add(additional).ok_or(AllocError::CapacityOverflow)?;
+      return self.set_capacity(cap);
+    }
+
+    /// Ensures capacity for at least `len + additional` elements, growing
+    /// geometrically.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+
+    #[inline(always)]
+    pub fn reserve(&mut self, len: usize, additional: usize) {
+      if let Err(e) = self.try_reserve(len, additional) {
+        global::handle_alloc_error(e);
+      }
+    }
+
+    /// Ensures capacity for exactly `len + additional` elements, if it must
+    /// grow.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+
+    #[inline(always)]
+    pub fn reserve_exact(&mut self, len: usize, additional: usize) {
+      if let Err(e) = self.try_reserve_exact(len, additional) {
+        global::handle_alloc_error(e);
+      }
+    }
+
+    /// Shrinks the capacity to `cap`, if it is larger. Shrinking to zero
+    /// deallocates.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+
+    pub fn shrink_to(&mut self, cap: usize) {
+      if Self::IS_ZST || cap >= self.cap {
+        return;
+      }
+
+      if let Err(e) = self.set_capacity(cap) {
+        global::handle_alloc_error(e);
+      }
+    }
+
+    /// Inserts `value` at `index`, shifting the elements in `index .. len`
+    /// up by one.
+    ///
+    /// # SAFETY
+    ///
+    /// - `index <= len < capacity`, and
+    /// - elements `0 .. len` must be initialized.
+
+    #[inline(always)]
+    pub unsafe fn insert_at(&mut self, len: usize, index: usize, value: T) {
+      debug_assert!(index <= len && len < self.cap);
+
+      let x = self.ptr + index;
+      unsafe { x.copy_to(x + 1usize, len - index) };
+      unsafe { x.write(value) };
+    }
+
+    /// Removes and returns the value at `index`, shifting the elements in
+    /// `index + 1 .. len` down by one.
+    ///
+    /// # SAFETY
+    ///
+    /// - `index < len <= capacity`, and
+    /// - elements `0 .. len` must be initialized.
+
+    #[inline(always)]
+    pub unsafe fn remove_at(&mut self, len: usize, index: usize) -> T {
+      debug_assert!(index < len && len <= self.cap);
+
+      let x = self.ptr + index;
+      let value = unsafe { x.read() };
+      unsafe { x.copy_from(x + 1usize, len - index - 1) };
+      return value;
+    }
+  }
+
+  impl<T, A: Allocator + Default> Default for RawVec<T, A> {
+    #[inline(always)]
+    fn default() -> RawVec<T, A> {
+      return RawVec::new_in(A::default());
+    }
+  }
+
+  impl<T, A: Allocator> Drop for RawVec<T, A> {
+    fn drop(&mut self) {
+      if ! Self::IS_ZST && self.cap != 0 {
+        unsafe { self.alloc.dealloc_slice(self.ptr, self.cap) };
+      }
+    }
+  }
+
+  impl<T, A: Allocator> core::fmt::Debug for RawVec<T, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return f.debug_struct("RawVec").field("ptr", &self.ptr).field("cap", &self.cap).finish();
+    }
+  }
+}
+
+#[cfg(feature = "alloc")]
+pub mod thin {
+  //! Thin pointers to dynamically sized values.
+  //!
+  //! A thin pointer points to the payload of an allocation whose metadata,
+  //! a slice length or a vtable pointer, is stored in a header just before
+  //! the payload. Recovering the full pointer takes a single load.
+
+  use core::alloc::Layout;
+  use super::AllocError;
+  use super::DynMetadata;
+  use super::Pointee;
+  use super::fptr;
+  use super::global;
+  use super::ptr;
+
+  // Returns the layout of an allocation with a header of type `M` and the
+  // given payload, along with the offset of the payload. The header sits
+  // immediately before the payload.
+
+  #[inline(always)]
+  fn layout<M>(payload: Layout) -> Result<(Layout, usize), AllocError> {
+    let (layout, offset) = Layout::new::<M>().extend(payload).map_err(|_| AllocError::CapacityOverflow)?;
+    return Ok((layout.pad_to_align(), offset));
+  }
+
+  #[inline(always)]
+  fn alloc<M>(payload: Layout, metadata: M) -> ptr<()> {
+    let (layout, offset) = match layout::<M>(payload) { Ok(x) => x, Err(e) => global::handle_alloc_error(e) };
+    let x = unsafe { global::alloc_layout::<u8>(layout) }.byte_add(offset);
+    unsafe { x.byte_sub::<M>(size_of::<M>()).write(metadata) };
+    return x;
+  }
+
+  #[inline(always)]
+  unsafe fn dealloc<M>(x: ptr<()>, payload: Layout) {
+    let (layout, offset) = unsafe { layout::<M>(payload).unwrap_unchecked() };
+    unsafe { global::dealloc_layout(x.byte_sub::<u8>(offset), layout) };
+  }
+
+  /// A thin pointer to a slice, with the length stored in a header.
+
+  #[allow(non_camel_case_types)]
+  #[repr(transparent)]
+  pub struct thin_slice<T>(ptr<T>);
+
+  impl<T> Clone for thin_slice<T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+      return *self;
+    }
+  }
+
+  impl<T> Copy for thin_slice<T> {
+  }
+
+  impl<T> Eq for thin_slice<T> {
+  }
+
+  impl<T> PartialEq for thin_slice<T> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+      return self.0 == other.0;
+    }
+  }
+
+  impl<T> thin_slice<T> {
+    /// Allocates a slice of `len` elements, initializing element `i` to
+    /// `f(i)`.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return. If `f` panics, the allocation is leaked.
+
+    pub fn from_fn<F>(len: usize, mut f: F) -> thin_slice<T>
+    where
+      F: FnMut(usize) -> T
+    {
+      let payload = match super::slice_layout::<T>(len) { Ok(x) => x, Err(e) => global::handle_alloc_error(e) };
+      let x = alloc::<usize>(payload, len).cast::<T>();
+
+      for i in 0 .. len {
+        unsafe { (x + i).write(f(i)) };
+      }
+
+      return thin_slice(x);
+    }
+
+    /// Allocates a copy of a slice.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+
+    pub fn from_slice(src: &[T]) -> thin_slice<T>
+    where
+      T: Clone
+    {
+      return thin_slice::from_fn(src.len(), |i| src[i].clone());
+    }
+
+    /// The pointer to the first element.
+
+    #[inline(always)]
+    pub fn as_ptr(self) -> ptr<T> {
+      return self.0;
+    }
+
+    /// The length of the slice.
+    ///
+    /// # SAFETY
+    ///
+    /// The allocation must be live.
+
+    #[inline(always)]
+    pub unsafe fn len(self) -> usize {
+      return unsafe { self.0.byte_sub::<usize>(size_of::<usize>()).read() };
+    }
+
+    /// Whether the slice is empty.
+    ///
+    /// # SAFETY
+    ///
+    /// The allocation must be live.
+
+    #[inline(always)]
+    pub unsafe fn is_empty(self) -> bool {
+      return unsafe { self.len() } == 0;
+    }
+
+    /// Converts into a full slice pointer.
+    ///
+    /// # SAFETY
+    ///
+    /// The allocation must be live.
+
+    #[inline(always)]
+    pub unsafe fn as_fptr(self) -> fptr<[T]> {
+      return fptr::from_slice_parts(self.0, unsafe { self.len() });
+    }
+
+    /// Converts into a `&[T]`.
+    ///
+    /// # SAFETY
+    ///
+    /// The reference must be be valid for the lifetime.
+
+    #[inline(always)]
+    pub unsafe fn as_ref<'a>(self) -> &'a [T] {
+      return unsafe { self.as_fptr().as_ref() };
+    }
+
+    /// Converts into a `&mut [T]`.
+    ///
+    /// # SAFETY
+    ///
+    /// The reference must be be valid for the lifetime.
+
+    #[inline(always)]
+    pub unsafe fn as_mut_ref<'a>(self) -> &'a mut [T] {
+      return unsafe { self.as_fptr().as_mut_ref() };
+    }
+
+    /// Drops the elements and deallocates.
+    ///
+    /// # SAFETY
+    ///
+    /// The allocation must be live, and is not live afterwards.
+
+    pub unsafe fn drop_and_dealloc(self) {
+      let len = unsafe { self.len() };
+      let payload = unsafe { Layout::from_size_align_unchecked(len * size_of::<T>(), align_of::<T>()) };
+      unsafe { self.as_fptr().drop_in_place() };
+      unsafe { dealloc::<usize>(self.0.cast(), payload) };
+    }
+  }
+
+  impl<T> core::fmt::Debug for thin_slice<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return core::fmt::Debug::fmt(&self.0, f);
+    }
+  }
+
+  /// A thin pointer to a trait object, with the vtable pointer stored in a
+  /// header.
+  ///
+  /// `T` is a trait object type that implements [`Pointee`], for example by
+  /// way of [`dyn_pointee!`](crate::dyn_pointee).
+
+  #[allow(non_camel_case_types)]
+  #[repr(transparent)]
+  pub struct thin_dyn<T: ?Sized>(ptr<()>, core::marker::PhantomData<fn(*mut T) -> *mut T>);
+
+  impl<T: ?Sized> Clone for thin_dyn<T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+      return *self;
+    }
+  }
+
+  impl<T: ?Sized> Copy for thin_dyn<T> {
+  }
+
+  impl<T: ?Sized> Eq for thin_dyn<T> {
+  }
+
+  impl<T: ?Sized> PartialEq for thin_dyn<T> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+      return self.0 == other.0;
+    }
+  }
+
+  impl<T: ?Sized + Pointee<Metadata = DynMetadata<T>>> thin_dyn<T> {
+    /// Allocates a `U` and erases it to `T`.
+    ///
+    /// Because unsizing coercions can't be expressed generically, `coerce`
+    /// does the erasing, and is typically `|x| x`. Returning a different
+    /// address panics.
+    ///
+    /// On failure, calls [`alloc::alloc::handle_alloc_error`] and does not
+    /// return.
+    ///
+    /// # SAFETY
+    ///
+    /// `coerce` must be an unsizing coercion from `U` to `T`, so that the
+    /// metadata it produces is the vtable of `U` as a `T`. It is called with a
+    /// dangling pointer, which it must not dereference.
+
+    pub unsafe fn new<U>(value: U, coerce: fn(*mut U) -> *mut T) -> thin_dyn<T> {
+      let x = ptr::<U>::invalid(align_of::<U>());
+      let y = fptr::from(coerce(x.as_mut_ptr()));
+      assert!(y.addr() == x.addr(), "coercion changed the address");
+
+      let x = alloc::<DynMetadata<T>>(Layout::new::<U>(), y.metadata());
+      unsafe { x.cast::<U>().write(value) };
+      return thin_dyn(x, core::marker::PhantomData);
+    }
+
+    /// The pointer to the value.
+
+    #[inline(always)]
+    pub fn as_ptr(self) -> ptr<()> {
+      return self.0;
+    }
+
+    /// Converts into a full trait object pointer.
+    ///
+    /// # SAFETY
+    ///
+    /// The allocation must be live.
+
+    #[inline(always)]
+    pub unsafe fn as_fptr(self) -> fptr<T> {
+      let metadata = unsafe { self.0.byte_sub::<DynMetadata<T>>(size_of::<DynMetadata<T>>()).read() };
+      return fptr::from_raw_parts(self.0, metadata);
+    }
+
+    /// Converts into a `&T`.
+    ///
+    /// # SAFETY
+    ///
+    /// The reference must be be valid for the lifetime.
+
+    #[inline(always)]
+    pub unsafe fn as_ref<'a>(self) -> &'a T {
+      return unsafe { self.as_fptr().as_ref() };
+    }
+
+    /// Converts into a `&mut T`.
+    ///
+    /// # SAFETY
+    ///
+    /// The reference must be be valid for the lifetime.
+
+    #[inline(always)]
+    pub unsafe fn as_mut_ref<'a>(self) -> &'a mut T {
+      return unsafe { self.as_fptr().as_mut_ref() };
+    }
+
+    /// Drops the value and deallocates.
+    ///
+    /// # SAFETY
+    ///
+    /// The allocation must be live, and is not live afterwards.
+
+    pub unsafe fn drop_and_dealloc(self) {
+      let x = unsafe { self.as_fptr() };
+      let payload = Layout::for_value(unsafe { x.as_ref() });
+      unsafe { x.drop_in_place() };
+      unsafe { dealloc::<DynMetadata<T>>(self.0, payload) };
+    }
+  }
+
+  impl<T: ?Sized> core::fmt::Debug for thin_dyn<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return core::fmt::Debug::fmt(&self.0, f);
+    }
+  }
+}
+
+pub mod intrusive {
+  //! Intrusive data structures.
+  //!
+  //! The links live inside the user's nodes, which are addressed by `ptr`.
+  //! Nothing is allocated and no references to nodes are created. An
+  //! [`Adapter`] maps between a node and one of its links, so a node with
+  //! several links can be in several structures at once.
+
+  use core::marker::PhantomData;
+  use super::ptr;
+
+  /// Maps between nodes and the links embedded in them.
+  ///
+  /// Usually implemented with [`intrusive_adapter!`](crate::intrusive_adapter).
+  ///
+  /// # SAFETY
+  ///
+  /// `from_link(to_link(x))` must equal `x`, and `to_link(x)` must point
+  /// into the node at `x`.
+
+  pub unsafe trait Adapter {
+    /// The node type.
+
+    type Node;
+
+    /// The link type.
+
+    type Link;
+
+    /// The link in a node.
+
+    fn to_link(x: ptr<Self::Node>) -> ptr<Self::Link>;
+
+    /// The node containing a link.
+
+    fn from_link(x: ptr<Self::Link>) -> ptr<Self::Node>;
+  }
+
+  /// Defines an [`Adapter`] for a link field.
+  ///
+  /// ```
+  /// use pop::intrusive::Link;
+  ///
+  /// struct Task { id: u32, run: Link, wait: Link }
+  ///
+  /// pop::intrusive_adapter!(struct RunQueue = Task { run: Link });
+  /// pop::intrusive_adapter!(struct WaitQueue = Task { wait: Link });
+  /// ```
+
+  #[macro_export]
+  macro_rules! intrusive_adapter {
+    ($vis:vis struct $name:ident = $node:ty { $field:ident : $link:ty } $(;)?) => {
+      $vis struct $name;
+
+      unsafe impl $crate::intrusive::Adapter for $name {
+        type Node = $node;
+
+        type Link = $link;
+
+        #[inline(always)]
+        fn to_link(x: $crate::ptr<$node>) -> $crate::ptr<$link> {
+          return x.byte_add(::core::mem::offset_of!($node, $field));
+        }
+
+        #[inline(always)]
+        fn from_link(x: $crate::ptr<$link>) -> $crate::ptr<$node> {
+          return $crate::container_of!(x, $node, $field);
+        }
+      }
+    };
+  }
+
+  /// A link in a [`List`].
+
+  #[derive(Debug)]
+  pub struct Link {
+    prev: ptr<Link>,
+    next: ptr<Link>,
+  }
+
+  impl Link {
+    /// Creates an unlinked link.
+
+    #[inline(always)]
+    pub const fn new() -> Link {
+      return Link { prev: ptr::NULL, next: ptr::NULL };
+    }
+  }
+
+  impl Default for Link {
+    #[inline(always)]
+    fn default() -> Link {
+      return Link::new();
+    }
+  }
+
+  #[inline(always)]
+  unsafe fn prev(x: ptr<Link>) -> ptr<Link> {
+    return unsafe { (*x.as_const_ptr()).prev };
+  }
+
+  #[inline(always)]
+  unsafe fn next(x: ptr<Link>) -> ptr<Link> {
+    return unsafe { (*x.as_const_ptr()).next };
+  }
+
+  #[inline(always)]
+  unsafe fn set_prev(x: ptr<Link>, y: ptr<Link>) {
+    unsafe { (*x.as_mut_ptr()).prev = y };
+  }
+
+  #[inline(always)]
+  unsafe fn set_next(x: ptr<Link>, y: ptr<Link>) {
+    unsafe { (*x.as_mut_ptr()).next = y };
+  }
+
+  /// An intrusive doubly-linked list.
+  ///
+  /// The list does not own its nodes. Dropping it leaves them untouched.
+
+  pub struct List<A: Adapter<Link = Link>> {
+    head: ptr<Link>,
+    tail: ptr<Link>,
+    len: usize,
+    _phantom_data: PhantomData<fn(A) -> A>,
+  }
+
+  impl<A: Adapter<Link = Link>> List<A> {
+    /// Creates an empty list.
+
+    #[inline(always)]
+    pub const fn new() -> List<A> {
+      return List { head: ptr::NULL, tail: ptr::NULL, len: 0, _phantom_data: PhantomData };
+    }
+
+    /// Whether the list is empty.
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+      return self.len == 0;
+    }
+
+    /// The number of nodes.
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+      return self.len;
+    }
+
+    /// The first node.
+
+    #[inline(always)]
+    pub fn front(&self) -> Option<ptr<A::Node>> {
+      return if self.head.is_null() { None } else { Some(A::from_link(self.head)) };
+    }
+
+    /// The last node.
+
+    #[inline(always)]
+    pub fn back(&self) -> Option<ptr<A::Node>> {
+      return if self.tail.is_null() { None } else { Some(A::from_link(self.tail)) };
+    }
+
+    /// Links `x` between `prev` and `next`, either of which may be null for
+    /// the ends of the list.
+
+    #[inline(always)]
+    unsafe fn link(&mut self, x: ptr<Link>, prev: ptr<Link>, next: ptr<Link>) {
+      unsafe { set_prev(x, prev) };
+      unsafe { set_next(x, next) };
+
+      if prev.is_null() {
+        self.head = x;
+      } else {
+        unsafe { set_next(prev, x) };
+      }
+
+      if next.is_null() {
+        self.tail = x;
+      } else {
+        unsafe { set_prev(next, x) };
+      }
+
+      self.len += 1;
+    }
+
+    unsafe fn unlink(&mut self, x: ptr<Link>) {
+      let p = unsafe { prev(x) };
+      let n = unsafe { next(x) };
+
+      if p.is_null() {
+        self.head = n;
+      } else {
+        unsafe { set_next(p, n) };
+      }
+
+      if n.is_null() {
+        self.tail = p;
+      } else {
+        unsafe { set_prev(n, p) };
+      }
+
+      unsafe { set_prev(x, ptr::NULL) };
+      unsafe { set_next(x, ptr::NULL) };
+      self.len -= 1;
+    }
+
+    /// Inserts a node at the front.
+    ///
+    /// # SAFETY
+    ///
+    /// `x` must be a valid node that is not in any list through this link,
+    /// and must stay valid while it is in this list.
+
+    #[inline(always)]
+    pub unsafe fn push_front(&mut self, x: ptr<A::Node>) {
+      unsafe { self.link(A::to_link(x), ptr::NULL, self.head) };
+    }
+
+    /// Inserts a node at the back.
+    ///
+    /// # SAFETY
+    ///
+    /// `x` must be a valid node that is not in any list through this link,
+    /// and must stay valid while it is in this list.
+
+    #[inline(always)]
+    pub unsafe fn push_back(&mut self, x: ptr<A::Node>) {
+      unsafe { self.link(A::to_link(x), self.tail, ptr::NULL) };
+    }
+
+    /// Removes and returns the first node.
+
+    #[inline(always)]
+    pub fn pop_front(&mut self) -> Option<ptr<A::Node>> {
+      let x = self.front()?;
+      unsafe { self.unlink(A::to_link(x)) };
+      return Some(x);
+    }
+
+    /// Removes and returns the last node.
+
+    #[inline(always)]
+    pub fn pop_back(&mut self) -> Option<ptr<A::Node>> {
+      let x = self.back()?;
+      unsafe { self.unlink(A::to_link(x)) };
+      return Some(x);
+    }
+
+    /// Removes a node.
+    ///
+    /// # SAFETY
+    ///
+    /// `x` must be in this list.
+
+    #[inline(always)]
+    pub unsafe fn remove(&mut self, x: ptr<A::Node>) {
+      unsafe { self.unlink(A::to_link(x)) };
+    }
+
+    /// Moves all nodes of `other` to the back of `self`, leaving `other`
+    /// empty.
+
+    #[inline(always)]
+    pub fn append(&mut self, other: &mut List<A>) {
+      CursorMut { list: self, current: ptr::NULL }.splice_before(other);
+    }
+
+    /// Forgets all nodes, leaving their links as they are.
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+      *self = List::new();
+    }
+
+    /// An iterator over the nodes, from front to back.
+
+    #[inline(always)]
+    pub fn iter(&self) -> Iter<'_, A> {
+      return Iter { head: self.head, tail: self.tail, len: self.len, _phantom_data: PhantomData };
+    }
+
+    /// A read-only cursor at the first node.
+
+    #[inline(always)]
+    pub fn cursor(&self) -> Cursor<'_, A> {
+      return Cursor { list: self, current: self.head };
+    }
+
+    /// A cursor at the first node.
+
+    #[inline(always)]
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, A> {
+      let current = self.head;
+      return CursorMut { list: self, current };
+    }
+
+    /// A cursor at `x`.
+    ///
+    /// # SAFETY
+    ///
+    /// `x` must be in this list.
+
+    #[inline(always)]
+    pub unsafe fn cursor_mut_at(&mut self, x: ptr<A::Node>) -> CursorMut<'_, A> {
+      return CursorMut { list: self, current: A::to_link(x) };
+    }
+  }
+
+  impl<A: Adapter<Link = Link>> Default for List<A> {
+    #[inline(always)]
+    fn default() -> List<A> {
+      return List::new();
+    }
+  }
+
+  impl<A: Adapter<Link = Link>> core::fmt::Debug for List<A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return f.debug_list().entries(self.iter()).finish();
+    }
+  }
+
+  impl<'a, A: Adapter<Link = Link>> IntoIterator for &'a List<A> {
+    type Item = ptr<A::Node>;
+
+    type IntoIter = Iter<'a, A>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Iter<'a, A> {
+      return self.iter();
+    }
+  }
+
+  /// An iterator over the nodes of a [`List`].
+
+  pub struct Iter<'a, A: Adapter<Link = Link>> {
+    head: ptr<Link>,
+    tail: ptr<Link>,
+    len: usize,
+    _phantom_data: PhantomData<&'a List<A>>,
+  }
+
+  impl<'a, A: Adapter<Link = Link>> Iterator for Iter<'a, A> {
+    type Item = ptr<A::Node>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<ptr<A::Node>> {
+      if self.len == 0 {
+        return None;
+      }
+
+      let x = self.head;
+      self.head = unsafe { next(x) };
+      self.len -= 1;
+      return Some(A::from_link(x));
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+      return (self.len, Some(self.len));
+    }
+  }
+
+  impl<'a, A: Adapter<Link = Link>> DoubleEndedIterator for Iter<'a, A> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<ptr<A::Node>> {
+      if self.len == 0 {
+        return None;
+      }
+
+      let x = self.tail;
+      self.tail = unsafe { prev(x) };
+      self.len -= 1;
+      return Some(A::from_link(x));
+    }
+  }
+
+  impl<'a, A: Adapter<Link = Link>> ExactSizeIterator for Iter<'a, A> {
+  }
+
+  /// A read-only cursor into a [`List`].
+  ///
+  /// A cursor is either at a node or at the "ghost" position past both ends,
+  /// from which moving forward goes to the front and moving backward goes to
+  /// the back.
+
+  pub struct Cursor<'a, A: Adapter<Link = Link>> {
+    list: &'a List<A>,
+    current: ptr<Link>,
+  }
+
+  impl<'a, A: Adapter<Link = Link>> Cursor<'a, A> {
+    /// The current node, or `None` at the ghost position.
+
+    #[inline(always)]
+    pub fn get(&self) -> Option<ptr<A::Node>> {
+      return if self.current.is_null() { None } else { Some(A::from_link(self.current)) };
+    }
+
+    /// Moves to the next node.
+
+    #[inline(always)]
+    pub fn move_next(&mut self) {
+      self.current = if self.current.is_null() { self.list.head } else { unsafe { next(self.current) } };
+    }
+
+    /// Moves to the previous node.
+
+    #[inline(always)]
+    pub fn move_prev(&mut self) {
+      self.current = if self.current.is_null() { self.list.tail } else { unsafe { prev(self.current) } };
+    }
+  }
+
+  /// A cursor into a [`List`] that can edit the list.
+  ///
+  /// See [`Cursor`] for the ghost position.
+
+  pub struct CursorMut<'a, A: Adapter<Link = Link>> {
+    list: &'a mut List<A>,
+    current: ptr<Link>,
+  }
+
+  impl<'a, A: Adapter<Link = Link>> CursorMut<'a, A> {
+    /// The current node, or `None` at the ghost position.
+
+    #[inline(always)]
+    pub fn get(&self) -> Option<ptr<A::Node>> {
+      return if self.current.is_null() { None } else { Some(A::from_link(self.current)) };
+    }
+
+    /// Moves to the next node.
+
+    #[inline(always)]
+    pub fn move_next(&mut self) {
+      self.current = if self.current.is_null() { self.list.head } else { unsafe { next(self.current) } };
+    }
+
+    /// Moves to the previous node.
+
+    #[inline(always)]
+    pub fn move_prev(&mut self) {
+      self.current = if self.current.is_null() { self.list.tail } else { unsafe { prev(self.current) } };
+    }
+
+    #[inline(always)]
+    fn prev_and_next(&self) -> (ptr<Link>, ptr<Link>) {
+      if self.current.is_null() {
+        return (self.list.tail, self.list.head);
+      }
+
+      return (unsafe { prev(self.current) }, unsafe { next(self.current) });
+    }
+
+    /// Inserts a node before the current one. At the ghost position, inserts
+    /// at the back.
+    ///
+    /// # SAFETY
+    ///
+    /// See [`List::push_front`].
+
+    #[inline(always)]
+    pub unsafe fn insert_before(&mut self, x: ptr<A::Node>) {
+      let p = self.prev_and_next().0;
+      let n = if self.current.is_null() { ptr::NULL } else { self.current };
+      unsafe { self.list.link(A::to_link(x), p, n) };
+    }
+
+    /// Inserts a node after the current one. At the ghost position, inserts
+    /// at the front.
+    ///
+    /// # SAFETY
+    ///
+    /// See [`List::push_front`].
+
+    #[inline(always)]
+    pub unsafe fn insert_after(&mut self, x: ptr<A::Node>) {
+      let n = self.prev_and_next().1;
+      let p = if self.current.is_null() { ptr::NULL } else { self.current };
+      unsafe { self.list.link(A::to_link(x), p, n) };
+    }
+
+    /// Removes the current node and moves to the next one. At the ghost
+    /// position, does nothing and returns `None`.
+
+    #[inline(always)]
+    pub fn remove_current(&mut self) -> Option<ptr<A::Node>> {
+      if self.current.is_null() {
+        return None;
+      }
+
+      let x = self.current;
+      self.current = unsafe { next(x) };
+      unsafe { self.list.unlink(x) };
+      return Some(A::from_link(x));
+    }
+
+    /// Moves all nodes of `other` to between `a` and `b`.
+
+    #[inline(always)]
+    fn splice(&mut self, a: ptr<Link>, b: ptr<Link>, other: &mut List<A>) {
+      if other.is_empty() {
+        return;
+      }
+
+      let head = other.head;
+      let tail = other.tail;
+
+      unsafe { set_prev(head, a) };
+      unsafe { set_next(tail, b) };
+
+      if a.is_null() {
+        self.list.head = head;
+      } else {
+        unsafe { set_next(a, head) };
+      }
+
+      if b.is_null() {
+        self.list.tail = tail;
+      } else {
+        unsafe { set_prev(b, tail) };
+      }
+
+      self.list.len += other.len;
+      other.clear();
+    }
+
+    /// Moves all nodes of `other` to before the current node, leaving `other`
+    /// empty. At the ghost position, moves them to the back.
+
+    #[inline(always)]
+    pub fn splice_before(&mut self, other: &mut List<A>) {
+      let p = self.prev_and_next().0;
+      let n = self.current;
+      self.splice(p, n, other);
+    }
+
+    /// Moves all nodes of `other` to after the current node, leaving `other`
+    /// empty. At the ghost position, moves them to the front.
+
+    #[inline(always)]
+    pub fn splice_after(&mut self, other: &mut List<A>) {
+      let n = self.prev_and_next().1;
+      let p = self.current;
+      self.splice(p, n, other);
+    }
+  }
+
+  /// A link in a [`Tree`].
+
+  #[derive(Debug)]
+  pub struct TreeLink {
+    parent: ptr<TreeLink>,
+    left: ptr<TreeLink>,
+    right: ptr<TreeLink>,
+    height: u32,
+  }
+
+  impl TreeLink {
+    /// Creates an unlinked link.
+
+    #[inline(always)]
+    pub const fn new() -> TreeLink {
+      return TreeLink { parent: ptr::NULL, left: ptr::NULL, right: ptr::NULL, height: 0 };
+    }
+  }
+
+  impl Default for TreeLink {
+    #[inline(always)]
+    fn default() -> TreeLink {
+      return TreeLink::new();
+    }
+  }
+
+  mod tree {
+    use super::TreeLink;
+    use super::ptr;
+
+    #[inline(always)]
+    pub(super) unsafe fn parent(x: ptr<TreeLink>) -> ptr<TreeLink> {
+      return unsafe { (*x.as_const_ptr()).parent };
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn left(x: ptr<TreeLink>) -> ptr<TreeLink> {
+      return unsafe { (*x.as_const_ptr()).left };
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn right(x: ptr<TreeLink>) -> ptr<TreeLink> {
+      return unsafe { (*x.as_const_ptr()).right };
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn height(x: ptr<TreeLink>) -> u32 {
+      return if x.is_null() { 0 } else { unsafe { (*x.as_const_ptr()).height } };
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn set_parent(x: ptr<TreeLink>, y: ptr<TreeLink>) {
+      unsafe { (*x.as_mut_ptr()).parent = y };
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn set_left(x: ptr<TreeLink>, y: ptr<TreeLink>) {
+      unsafe { (*x.as_mut_ptr()).left = y };
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn set_right(x: ptr<TreeLink>, y: ptr<TreeLink>) {
+      unsafe { (*x.as_mut_ptr()).right = y };
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn set_height(x: ptr<TreeLink>, h: u32) {
+      unsafe { (*x.as_mut_ptr()).height = h };
+    }
+
+    #[inline(always)]
+    pub(super) unsafe fn update_height(x: ptr<TreeLink>) {
+      unsafe { set_height(x, 1 + height(left(x)).max(height(right(x)))) };
+    }
+
+    pub(super) unsafe fn first(mut x: ptr<TreeLink>) -> ptr<TreeLink> {
+      if x.is_null() {
+        return x;
+      }
+
+      while ! unsafe { left(x) }.is_null() {
+        x = unsafe { left(x) };
+      }
+
+      return x;
+    }
+
+    pub(super) unsafe fn last(mut x: ptr<TreeLink>) -> ptr<TreeLink> {
+      if x.is_null() {
+        return x;
+      }
+
+      while ! unsafe { right(x) }.is_null() {
+        x = unsafe { right(x) };
+      }
+
+      return x;
+    }
+
+    pub(super) unsafe fn next(mut x: ptr<TreeLink>) -> ptr<TreeLink> {
+      if ! unsafe { right(x) }.is_null() {
+        return unsafe { first(right(x)) };
+      }
+
+      let mut p = unsafe { parent(x) };
+
+      while ! p.is_null() && unsafe { right(p) } == x {
+        x = p;
+        p = unsafe { parent(p) };
+      }
+
+      return p;
+    }
+
+    pub(super) unsafe fn prev(mut x: ptr<TreeLink>) -> ptr<TreeLink> {
+      if ! unsafe { left(x) }.is_null() {
+        return unsafe { last(left(x)) };
+      }
+
+      let mut p = unsafe { parent(x) };
+
+      while ! p.is_null() && unsafe { left(p) } == x {
+        x = p;
+        p = unsafe { parent(p) };
+      }
+
+      return p;
+    }
+  }
+
+  /// An intrusive ordered tree, balanced as an AVL tree.
+  ///
+  /// The order is given by comparator closures passed to [`Tree::insert`] and
+  /// the search methods, which must agree with each other. Equal nodes are
+  /// kept in insertion order.
+  ///
+  /// The tree does not own its nodes. Dropping it leaves them untouched.
+
+  pub struct Tree<A: Adapter<Link = TreeLink>> {
+    root: ptr<TreeLink>,
+    len: usize,
+    _phantom_data: PhantomData<fn(A) -> A>,
+  }
+
+  impl<A: Adapter<Link = TreeLink>> Tree<A> {
+    /// Creates an empty tree.
+
+    #[inline(always)]
+    pub const fn new() -> Tree<A> {
+      return Tree { root: ptr::NULL, len: 0, _phantom_data: PhantomData };
+    }
+
+    /// Whether the tree is empty.
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+      return self.len == 0;
+    }
+
+    /// The number of nodes.
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+      return self.len;
+    }
+
+    #[inline(always)]
+    fn node(x: ptr<TreeLink>) -> Option<ptr<A::Node>> {
+      return if x.is_null() { None } else { Some(A::from_link(x)) };
+    }
+
+    /// The least node.
+
+    #[inline(always)]
+    pub fn first(&self) -> Option<ptr<A::Node>> {
+      return Self::node(unsafe { tree::first(self.root) });
+    }
+
+    /// The greatest node.
+
+    #[inline(always)]
+    pub fn last(&self) -> Option<ptr<A::Node>> {
+      return Self::node(unsafe { tree::last(self.root) });
+    }
+
+    #[inline(always)]
+    unsafe fn replace_child(&mut self, p: ptr<TreeLink>, old: ptr<TreeLink>, new: ptr<TreeLink>) {
+      if p.is_null() {
+        self.root = new;
+      } else if unsafe { tree::left(p) } == old {
+        unsafe { tree::set_left(p, new) };
+      } else {
+        unsafe { tree::set_right(p, new) };
+      }
+    }
+
+    unsafe fn rotate_left(&mut self, x: ptr<TreeLink>) -> ptr<TreeLink> {
+      let y = unsafe { tree::right(x) };
+      let b = unsafe { tree::left(y) };
+      let p = unsafe { tree::parent(x) };
+
+      unsafe { tree::set_right(x, b) };
+      if ! b.is_null() { unsafe { tree::set_parent(b, x) } };
+      unsafe { tree::set_parent(y, p) };
+      unsafe { self.replace_child(p, x, y) };
+      unsafe { tree::set_left(y, x) };
+      unsafe { tree::set_parent(x, y) };
+      unsafe { tree::update_height(x) };
+      unsafe { tree::update_height(y) };
+      return y;
+    }
+
+    unsafe fn rotate_right(&mut self, x: ptr<TreeLink>) -> ptr<TreeLink> {
+      let y = unsafe { tree::left(x) };
+      let b = unsafe { tree::right(y) };
+      let p = unsafe { tree::parent(x) };
+
+      unsafe { tree::set_left(x, b) };
+      if ! b.is_null() { unsafe { tree::set_parent(b, x) } };
+      unsafe { tree::set_parent(y, p) };
+      unsafe { self.replace_child(p, x, y) };
+      unsafe { tree::set_right(y, x) };
+      unsafe { tree::set_parent(x, y) };
+      unsafe { tree::update_height(x) };
+      unsafe { tree::update_height(y) };
+      return y;
+    }
+
+    // Restores the height invariant on the path from `x` to the root.
+
+    unsafe fn rebalance(&mut self, mut x: ptr<TreeLink>) {
+      while ! x.is_null() {
+        let l = unsafe { tree::left(x) };
+        let r = unsafe { tree::right(x) };
+        let hl = unsafe { tree::height(l) };
+        let hr = unsafe { tree::height(r) };
+
+        if hl > hr + 1 {
+          if unsafe { tree::height(tree::left(l)) < tree::height(tree::right(l)) } {
+            let _ = unsafe { self.rotate_left(l) };
+          }
+          x = unsafe { self.rotate_right(x) };
+        } else if hr > hl + 1 {
+          if unsafe { tree::height(tree::right(r)) < tree::height(tree::left(r)) } {
+            let _ = unsafe { self.rotate_right(r) };
+          }
+          x = unsafe { self.rotate_left(x) };
+        } else {
+          unsafe { tree::set_height(x, 1 + hl.max(hr)) };
+        }
+
+        x = unsafe { tree::parent(x) };
+      }
+    }
+
+    /// Inserts a node.
+    ///
+    /// `f(y)` must return the ordering of each node `y` in the tree relative
+    /// to `x`. `x` goes after all nodes that compare equal to it.
+    ///
+    /// # SAFETY
+    ///
+    /// `x` must be a valid node that is not in any tree through this link,
+    /// and must stay valid while it is in this tree.
+
+    pub unsafe fn insert<F>(&mut self, x: ptr<A::Node>, mut f: F)
+    where
+      F: FnMut(ptr<A::Node>) -> core::cmp::Ordering
+    {
+      let x = A::to_link(x);
+      let mut p = ptr::NULL;
+      let mut y = self.root;
+      let mut is_left = false;
+
+      while ! y.is_null() {
+        p = y;
+        is_left = f(A::from_link(y)).is_gt();
+        y = if is_left { unsafe { tree::left(y) } } else { unsafe { tree::right(y) } };
+      }
+
+      unsafe { x.write(TreeLink { parent: p, left: ptr::NULL, right: ptr::NULL, height: 1 }) };
+
+      if p.is_null() {
+        self.root = x;
+      } else if is_left {
+        unsafe { tree::set_left(p, x) };
+      } else {
+        unsafe { tree::set_right(p, x) };
+      }
+
+      self.len += 1;
+      unsafe { self.rebalance(p) };
+    }
+
+    /// Removes a node.
+    ///
+    /// # SAFETY
+    ///
+    /// `x` must be in this tree.
+
+    pub unsafe fn remove(&mut self, x: ptr<A::Node>) {
+      let z = A::to_link(x);
+      let l = unsafe { tree::left(z) };
+      let r = unsafe { tree::right(z) };
+      let p = unsafe { tree::parent(z) };
+
+      let start =
+        if l.is_null() || r.is_null() {
+          let c = if l.is_null() { r } else { l };
+          if ! c.is_null() { unsafe { tree::set_parent(c, p) } };
+          unsafe { self.replace_child(p, z, c) };
+          p
+        } else {
+          // Put the successor `y` in the place of `z`.
+
+          let y = unsafe { tree::first(r) };
+          let start =
+            if y == r {
+              y
+            } else {
+              let yp = unsafe { tree::parent(y) };
+              let yr = unsafe { tree::right(y) };
+              unsafe { tree::set_left(yp, yr) };
+              if ! yr.is_null() { unsafe { tree::set_parent(yr, yp) } };
+              unsafe { tree::set_right(y, r) };
+              unsafe { tree::set_parent(r, y) };
+              yp
+            };
+          unsafe { tree::set_left(y, l) };
+          unsafe { tree::set_parent(l, y) };
+          unsafe { tree::set_parent(y, p) };
+          unsafe { tree::set_height(y, tree::height(z)) };
+          unsafe { self.replace_child(p, z, y) };
+          start
+        };
+
+      unsafe { z.write(TreeLink::new()) };
+      self.len -= 1;
+      unsafe { self.rebalance(start) };
+    }
+
+    /// The first node `y` for which `f(y)` is not `Less`.
+    ///
+    /// `f(y)` must return the ordering of `y` relative to the key being
+    /// searched for.
+
+    pub fn lower_bound<F>(&self, mut f: F) -> Option<ptr<A::Node>>
+    where
+      F: FnMut(ptr<A::Node>) -> core::cmp::Ordering
+    {
+      let mut result = ptr::NULL;
+      let mut y = self.root;
+
+      while ! y.is_null() {
+        if f(A::from_link(y)).is_lt() {
+          y = unsafe { tree::right(y) };
+        } else {
+          result = y;
+          y = unsafe { tree::left(y) };
+        }
+      }
+
+      return Self::node(result);
+    }
+
+    /// The first node `y` for which `f(y)` is `Greater`.
+    ///
+    /// `f(y)` must return the ordering of `y` relative to the key being
+    /// searched for.
+
+    pub fn upper_bound<F>(&self, mut f: F) -> Option<ptr<A::Node>>
+    where
+      F: FnMut(ptr<A::Node>) -> core::cmp::Ordering
+    {
+      let mut result = ptr::NULL;
+      let mut y = self.root;
+
+      while ! y.is_null() {
+        if f(A::from_link(y)).is_gt() {
+          result = y;
+          y = unsafe { tree::left(y) };
+        } else {
+          y = unsafe { tree::right(y) };
+        }
+      }
+
+      return Self::node(result);
+    }
+
+    /// The first node `y` for which `f(y)` is `Equal`.
+    ///
+    /// `f(y)` must return the ordering of `y` relative to the key being
+    /// searched for.
+
+    pub fn find<F>(&self, mut f: F) -> Option<ptr<A::Node>>
+    where
+      F: FnMut(ptr<A::Node>) -> core::cmp::Ordering
+    {
+      let x = self.lower_bound(&mut f)?;
+      return if f(x).is_eq() { Some(x) } else { None };
+    }
+
+    /// Forgets all nodes, leaving their links as they are.
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+      *self = Tree::new();
+    }
+
+    /// An iterator over the nodes, in order.
+
+    #[inline(always)]
+    pub fn iter(&self) -> TreeIter<'_, A> {
+      let head = unsafe { tree::first(self.root) };
+      let tail = unsafe { tree::last(self.root) };
+      return TreeIter { head, tail, len: self.len, _phantom_data: PhantomData };
+    }
+
+    /// A cursor at the least node.
+
+    #[inline(always)]
+    pub fn cursor(&self) -> TreeCursor<'_, A> {
+      return TreeCursor { tree: self, current: unsafe { tree::first(self.root) } };
+    }
+
+    /// A cursor at `x`.
+    ///
+    /// # SAFETY
+    ///
+    /// `x` must be in this tree.
+
+    #[inline(always)]
+    pub unsafe fn cursor_at(&self, x: ptr<A::Node>) -> TreeCursor<'_, A> {
+      return TreeCursor { tree: self, current: A::to_link(x) };
+    }
+  }
+
+  impl<A: Adapter<Link = TreeLink>> Default for Tree<A> {
+    #[inline(always)]
+    fn default() -> Tree<A> {
+      return Tree::new();
+    }
+  }
+
+  impl<A: Adapter<Link = TreeLink>> core::fmt::Debug for Tree<A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return f.debug_list().entries(self.iter()).finish();
+    }
+  }
+
+  impl<'a, A: Adapter<Link = TreeLink>> IntoIterator for &'a Tree<A> {
+    type Item = ptr<A::Node>;
+
+    type IntoIter = TreeIter<'a, A>;
+
+    #[inline(always)]
+    fn into_iter(self) -> TreeIter<'a, A> {
+      return self.iter();
+    }
+  }
+
+  /// An in-order iterator over the nodes of a [`Tree`].
+
+  pub struct TreeIter<'a, A: Adapter<Link = TreeLink>> {
+    head: ptr<TreeLink>,
+    tail: ptr<TreeLink>,
+    len: usize,
+    _phantom_data: PhantomData<&'a Tree<A>>,
+  }
+
+  impl<'a, A: Adapter<Link = TreeLink>> Iterator for TreeIter<'a, A> {
+    type Item = ptr<A::Node>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<ptr<A::Node>> {
+      if self.len == 0 {
+        return None;
+      }
+
+      let x = self.head;
+      self.head = unsafe { tree::next(x) };
+      self.len -= 1;
+      return Some(A::from_link(x));
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+      return (self.len, Some(self.len));
+    }
+  }
+
+  impl<'a, A: Adapter<Link = TreeLink>> DoubleEndedIterator for TreeIter<'a, A> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<ptr<A::Node>> {
+      if self.len == 0 {
+        return None;
+      }
+
+      let x = self.tail;
+      self.tail = unsafe { tree::prev(x) };
+      self.len -= 1;
+      return Some(A::from_link(x));
+    }
+  }
+
+  impl<'a, A: Adapter<Link = TreeLink>> ExactSizeIterator for TreeIter<'a, A> {
+  }
+
+  /// An in-order cursor into a [`Tree`].
+  ///
+  /// See [`Cursor`] for the ghost position.
+
+  pub struct TreeCursor<'a, A: Adapter<Link = TreeLink>> {
+    tree: &'a Tree<A>,
+    current: ptr<TreeLink>,
+  }
+
+  impl<'a, A: Adapter<Link = TreeLink>> TreeCursor<'a, A> {
+    /// The current node, or `None` at the ghost position.
+
+    #[inline(always)]
+    pub fn get(&self) -> Option<ptr<A::Node>> {
+      return Tree::<A>::node(self.current);
+    }
+
+    /// Moves to the next node.
+
+    #[inline(always)]
+    pub fn move_next(&mut self) {
+      self.current =
+        if self.current.is_null() {
+          unsafe { tree::first(self.tree.root) }
+        } else {
+          unsafe { tree::next(self.current) }
+        };
+    }
+
+    /// Moves to the previous node.
+
+    #[inline(always)]
+    pub fn move_prev(&mut self) {
+      self.current =
+        if self.current.is_null() {
+          unsafe { tree::last(self.tree.root) }
+        } else {
+          unsafe { tree::prev(self.current) }
+        };
+    }
+  }
+}
+
+#[cfg(feature = "alloc")]
+pub mod debug {
+  //! A sanitizing allocator for debugging.
+  //!
+  //! [`Sanitizer`] wraps the global allocator. It surrounds each block with
+  //! canary bytes, poisons freed blocks and holds them in a quarantine before
+  //! releasing them, so that out-of-bounds writes and writes after free can
+  //! be detected.
+
+  use core::alloc::Layout;
+  use core::cell::UnsafeCell;
+  use super::AllocError;
+  use super::Allocator;
+  use super::global;
+  use super::intrusive::Link;
+  use super::intrusive::List;
+  use super::ptr;
+
+  /// The number of canary bytes on each side of a block.
+
+  pub const REDZONE: usize = 16;
+
+  /// The byte written to redzones.
+
+  pub const CANARY: u8 = 0xfd;
+
+  /// The byte written to newly allocated memory.
+
+  pub const UNINIT: u8 = 0xcd;
+
+  /// The byte written to freed memory.
+
+  pub const POISON: u8 = 0xdd;
+
+  /// The default quarantine size in bytes.
+
+  pub const DEFAULT_QUARANTINE: usize = 1 << 20;
+
+  const LIVE: usize = 0x4c49_5645;
+  const FREED: usize = 0x4652_4545;
+
+  #[repr(C)]
+  struct Header {
+    link: Link,
+    layout: Layout,
+    offset: usize,
+    state: usize,
+  }
+
+  crate::intrusive_adapter!(struct HeaderAdapter = Header { link: Link });
+
+  impl Header {
+    #[inline(always)]
+    fn of(x: ptr<u8>) -> ptr<Header> {
+      return x.byte_sub(REDZONE + size_of::<Header>());
+    }
+
+    #[inline(always)]
+    fn payload(h: ptr<Header>) -> ptr<u8> {
+      return h.byte_add(size_of::<Header>() + REDZONE);
+    }
+
+    #[inline(always)]
+    fn block_layout(layout: Layout) -> Result<(Layout, usize), AllocError> {
+      let align = layout.align().max(align_of::<Header>());
+      let offset = (size_of::<Header>() + REDZONE).next_multiple_of(align);
+      let size =
+        offset.checked_add(layout.size())
+          .and_then(|n| n.checked_add(REDZONE))
+          .ok_or(AllocError::CapacityOverflow)?;
+      let block = Layout::from_size_align(size, align).map_err(|_| AllocError::CapacityOverflow)?;
+      return Ok((block, offset));
+    }
+  }
+
+  /// What went wrong.
+
+  #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+  pub enum Kind {
+    /// A byte before the block was overwritten.
+    BufferUnderflow,
+    /// A byte after the block was overwritten.
+    BufferOverflow,
+    /// A byte of a freed block was overwritten.
+    UseAfterFree,
+    /// A freed block was freed again.
+    DoubleFree,
+    /// A pointer that was not allocated by the sanitizer was freed.
+    InvalidFree,
+    /// A block was freed with a different layout than it was allocated with.
+    LayoutMismatch {
+      /// The layout passed to the deallocation.
+      given: Layout,
+    },
+  }
+
+  /// A detected memory error.
+
+  #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+  pub struct Report {
+    /// What went wrong.
+    pub kind: Kind,
+    /// The start of the block.
+    pub address: ptr<u8>,
+    /// The layout of the block as allocated, or as given for an invalid
+    /// free.
+    pub layout: Layout,
+    /// The first corrupted byte, or the start of the block if no byte is
+    /// at fault.
+    pub at: ptr<u8>,
+  }
+
+  impl core::fmt::Display for Report {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      let what = match self.kind {
+        Kind::BufferUnderflow => "buffer underflow",
+        Kind::BufferOverflow => "buffer overflow",
+        Kind::UseAfterFree => "use after free",
+        Kind::DoubleFree => "double free",
+        Kind::InvalidFree => "invalid free",
+        Kind::LayoutMismatch { .. } => "layout mismatch",
+      };
+
+      write!(
+        f,
+        "{} at {:?} in block {:?} with size {} and alignment {}",
+        what,
+        self.at,
+        self.address,
+        self.layout.size(),
+        self.layout.align()
+      )?;
+
+      if let Kind::LayoutMismatch { given } = self.kind {
+        write!(f, ", freed with size {} and alignment {}", given.size(), given.align())?;
+      }
+
+      return Ok(());
+    }
+  }
+
+  impl core::error::Error for Report {
+  }
+
+  #[inline(always)]
+  fn find_not(x: ptr<u8>, n: usize, byte: u8) -> Option<ptr<u8>> {
+    return (0 .. n).map(|i| x + i).find(|&y| unsafe { y.read_volatile() } != byte);
+  }
+
+  fn check_redzones(h: ptr<Header>) -> Result<(), Report> {
+    let x = Header::payload(h);
+    let layout = unsafe { (*h.as_const_ptr()).layout };
+    let report = |kind, at| Report { kind, address: x, layout, at };
+
+    if let Some(at) = find_not(x - REDZONE, REDZONE, CANARY) {
+      return Err(report(Kind::BufferUnderflow, at));
+    }
+
+    if let Some(at) = find_not(x + layout.size(), REDZONE, CANARY) {
+      return Err(report(Kind::BufferOverflow, at));
+    }
+
+    return Ok(());
+  }
+
+  fn check_poison(h: ptr<Header>) -> Result<(), Report> {
+    let x = Header::payload(h);
+    let layout = unsafe { (*h.as_const_ptr()).layout };
+
+    if let Some(at) = find_not(x, layout.size(), POISON) {
+      return Err(Report { kind: Kind::UseAfterFree, address: x, layout, at });
+    }
+
+    return Ok(());
+  }
+
+  // The state is only borrowed within a single method, which never calls back
+  // into the sanitizer, and `UnsafeCell` keeps the sanitizer from being
+  // `Sync`.
+
+  struct State {
+    live: List<HeaderAdapter>,
+    quarantine: List<HeaderAdapter>,
+    quarantined_bytes: usize,
+  }
+
+  /// An allocator that detects out-of-bounds writes, writes after free, and
+  /// invalid frees.
+  ///
+  /// Each block is surrounded by [`REDZONE`] bytes of [`CANARY`]. New memory
+  /// is filled with [`UNINIT`] and freed memory with [`POISON`]. Freed blocks
+  /// are held in a first-in, first-out quarantine until it exceeds its size,
+  /// and only then returned to the global allocator.
+  ///
+  /// Redzones are checked when a block is freed, and redzones and poison are
+  /// checked when a block leaves the quarantine or on [`Sanitizer::verify_heap`].
+  /// Errors found while freeing cause a panic with a [`Report`].
+  ///
+  /// Detection is best effort. Freeing a pointer that was never allocated,
+  /// or that already left the quarantine, reads memory the sanitizer does
+  /// not own. Blocks still live when the sanitizer is dropped are leaked.
+
+  pub struct Sanitizer {
+    state: UnsafeCell<State>,
+    quarantine_size: usize,
+  }
+
+  impl Sanitizer {
+    /// Creates a sanitizer with a quarantine of [`DEFAULT_QUARANTINE`] bytes.
+
+    #[inline(always)]
+    pub const fn new() -> Sanitizer {
+      return Sanitizer::with_quarantine(DEFAULT_QUARANTINE);
+    }
+
+    /// Creates a sanitizer that quarantines up to `size` bytes of freed
+    /// blocks.
+
+    #[inline(always)]
+    pub const fn with_quarantine(size: usize) -> Sanitizer {
+      let state = State { live: List::new(), quarantine: List::new(), quarantined_bytes: 0 };
+      return Sanitizer { state: UnsafeCell::new(state), quarantine_size: size };
+    }
+
+    /// The number of live blocks.
+
+    #[inline(always)]
+    pub fn live(&self) -> usize {
+      return unsafe { &*self.state.get() }.live.len();
+    }
+
+    /// The number of blocks in the quarantine.
+
+    #[inline(always)]
+    pub fn quarantined(&self) -> usize {
+      return unsafe { &*self.state.get() }.quarantine.len();
+    }
+
+    /// Checks the redzones of every live and quarantined block, and the
+    /// poison of every quarantined block.
+
+    pub fn verify_heap(&self) -> Result<(), Report> {
+      let state = unsafe { &*self.state.get() };
+
+      for h in state.live.iter() {
+        check_redzones(h)?;
+      }
+
+      for h in state.quarantine.iter() {
+        check_redzones(h)?;
+        check_poison(h)?;
+      }
+
+      return Ok(());
+    }
+
+    fn release(h: ptr<Header>) {
+      let Header { layout, offset, .. } = unsafe { h.read() };
+      let (block, _) = Header::block_layout(layout).unwrap();
+      unsafe { global::dealloc_layout(Header::payload(h) - offset, block) };
+    }
+
+    fn try_dealloc(&self, x: ptr<u8>, layout: Layout) -> Result<(), Report> {
+      let state = unsafe { &mut *self.state.get() };
+      let h = Header::of(x);
+      let allocated = unsafe { (*h.as_const_ptr()).layout };
+      let report = |kind, layout| Report { kind, address: x, layout, at: x };
+
+      match unsafe { (*h.as_const_ptr()).state } {
+        LIVE => {}
+        FREED => return Err(report(Kind::DoubleFree, allocated)),
+        _ => return Err(report(Kind::InvalidFree, layout)),
+      }
+
+      if allocated != layout {
+        return Err(report(Kind::LayoutMismatch { given: layout }, allocated));
+      }
+
+      check_redzones(h)?;
+
+      unsafe { state.live.remove(h) };
+      unsafe { x.write_bytes(POISON, layout.size()) };
+      unsafe { (*h.as_mut_ptr()).state = FREED };
+      unsafe { state.quarantine.push_back(h) };
+      state.quarantined_bytes += layout.size();
+
+      while state.quarantined_bytes > self.quarantine_size {
+        let Some(h) = state.quarantine.pop_front() else { break };
+        state.quarantined_bytes -= unsafe { (*h.as_const_ptr()).layout.size() };
+        check_redzones(h)?;
+        check_poison(h)?;
+        Sanitizer::release(h);
+      }
+
+      return Ok(());
+    }
+  }
+
+  impl Default for Sanitizer {
+    #[inline(always)]
+    fn default() -> Sanitizer {
+      return Sanitizer::new();
+    }
+  }
+
+  impl Drop for Sanitizer {
+    fn drop(&mut self) {
+      while let Some(h) = self.state.get_mut().quarantine.pop_front() {
+        Sanitizer::release(h);
+      }
+    }
+  }
+
+  impl core::fmt::Debug for Sanitizer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+      return
+        f.debug_struct("Sanitizer")
+          .field("live", &self.live())
+          .field("quarantined", &self.quarantined())
+          .field("quarantine_size", &self.quarantine_size)
+          .finish();
+    }
+  }
+
+  /// Deallocation panics with a [`Report`] if it detects an error.
+
+  unsafe impl Allocator for Sanitizer {
+    unsafe fn alloc_layout<T>(&self, layout: Layout) -> Result<ptr<T>, AllocError> {
+      let (block, offset) = Header::block_layout(layout)?;
+      let x = unsafe { global::try_alloc_layout::<u8>(block) }? + offset;
+      let h = Header::of(x);
+
+      unsafe { h.write(Header { link: Link::new(), layout, offset, state: LIVE }) };
+      unsafe { (x - REDZONE).write_bytes(CANARY, REDZONE) };
+      unsafe { x.write_bytes(UNINIT, layout.size()) };
+      unsafe { (x + layout.size()).write_bytes(CANARY, REDZONE) };
+      unsafe { (*self.state.get()).live.push_back(h) };
+
+      return Ok(x.cast());
+    }
+
+    unsafe fn dealloc_layout<T>(&self, x: ptr<T>, layout: Layout) {
+      if let Err(report) = self.try_dealloc(x.cast(), layout) {
+        panic!("{}", report);
+      }
+    }
   }
 }
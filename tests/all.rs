@@ -32,3 +32,854 @@ fn test_special_traits() {
   is_unpin::<ptr<T>>();
   is_unwind_safe::<ptr<T>>();
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_try_alloc_slice() {
+  use pop::AllocError;
+  use pop::global;
+
+  assert_eq!(unsafe { global::try_alloc_slice::<u64>(usize::MAX / 4) }, Err(AllocError::CapacityOverflow));
+
+  let x = unsafe { global::try_alloc_slice_zeroed::<u64>(4) }.unwrap();
+  assert!(unsafe { x.as_slice_ref(4) }.iter().all(|&a| a == 0));
+
+  let x = unsafe { global::try_realloc_slice(x, 4, 8) }.unwrap();
+  assert_eq!(unsafe { global::try_realloc_slice(x, 8, usize::MAX / 4) }, Err(AllocError::CapacityOverflow));
+
+  unsafe { global::dealloc_slice(x, 8) };
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_allocator() {
+  use pop::Allocator;
+  use pop::global::Global;
+
+  fn roundtrip<A: Allocator>(a: A) {
+    let x = unsafe { a.alloc_slice_zeroed::<u32>(3) }.unwrap();
+    unsafe { (x + 2usize).write(7) };
+    let x = unsafe { a.realloc_slice(x, 3, 5) }.unwrap();
+    assert_eq!(unsafe { x.as_slice_ref(3) }, &[0, 0, 7]);
+    unsafe { a.dealloc_slice(x, 5) };
+  }
+
+  roundtrip::<Global>(Global);
+  roundtrip::<&Global>(&Global);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_bump() {
+  use pop::arena::Bump;
+
+  let a = Bump::new();
+  let m = a.mark();
+  let x = a.alloc::<u8>();
+  let y = a.alloc::<u64>();
+  assert!(y.is_aligned());
+  assert!(x.addr() < y.addr());
+
+  let z = a.alloc_slice::<u32>(10_000);
+  unsafe { z.write_bytes(0, 10_000) };
+  let capacity = a.capacity();
+
+  unsafe { a.reset_to(m) };
+  assert_eq!(a.alloc::<u8>(), x);
+
+  a.reset();
+  assert_eq!(a.alloc::<u8>(), x);
+  let _ = a.alloc_slice::<u32>(10_000);
+  assert_eq!(a.capacity(), capacity);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_pool() {
+  use pop::pool::Pool;
+
+  fn is_send_sync<T: Send + Sync>() {}
+  is_send_sync::<Pool<u64>>();
+
+  let mut a = Pool::<[u64; 3]>::new();
+  let n = a.stats().slots_per_page + 1;
+  let xs = (0 .. n).map(|_| a.alloc()).collect::<Vec<_>>();
+  assert_eq!(a.stats().pages, 2);
+  assert_eq!(a.stats().live, n);
+
+  unsafe { a.free(xs[n - 1]) };
+  assert_eq!(a.stats().pages, 1);
+
+  unsafe { a.free(xs[0]) };
+  assert_eq!(a.alloc(), xs[0]);
+
+  for &x in &xs[.. n - 1] {
+    unsafe { a.free(x) };
+  }
+
+  assert_eq!(a.stats().pages, 0);
+  assert_eq!(a.stats().live, 0);
+}
+
+#[test]
+fn test_nptr() {
+  use pop::nptr;
+
+  assert_eq!(size_of::<Option<nptr<u64>>>(), size_of::<usize>());
+  assert_eq!(nptr::new(ptr::<u64>::NULL), None);
+
+  let mut a = [1u64, 2, 3];
+  let x = nptr::from(&mut a[..]);
+  let y = x + 2usize;
+  assert_eq!(y - x, 2);
+  assert_eq!(unsafe { y.read() }, 3);
+  assert_eq!(nptr::new(y.as_ptr()), Some(y));
+}
+
+#[test]
+#[should_panic]
+fn test_nptr_null_arithmetic() {
+  let _ = pop::nptr::<u8>::dangling() - 1usize;
+}
+
+#[test]
+fn test_tagged() {
+  use pop::tagged;
+
+  let a = 0u64;
+  let x = ptr::from(&a);
+  let y = tagged::<u64, 3>::new(x, 5);
+  assert_eq!(y.ptr(), x);
+  assert_eq!(y.tag(), 5);
+  assert_eq!(y.with_tag(2).tag(), 2);
+  assert_eq!(y.with_ptr(ptr::NULL).tag(), 5);
+  assert_eq!(unsafe { y.ptr().read() }, 0);
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn test_htagged() {
+  use pop::htagged;
+
+  let a = 7u64;
+  let x = ptr::from(&a);
+  let y = htagged::new(x, 0xbeef).unwrap();
+  assert_eq!(y.ptr(), x);
+  assert_eq!(y.payload(), 0xbeef);
+  assert_eq!(unsafe { y.read() }, 7);
+  assert_eq!(y.with_payload(1).payload(), 1);
+
+  assert!(htagged::<u64>::is_canonical(0xffff_8000_0000_0000));
+  assert!(! htagged::<u64>::is_canonical(0x0001_0000_0000_0000));
+  assert_eq!(htagged::new(ptr::<u64>::invalid(0x0001_0000_0000_0000), 0), None);
+  assert_eq!(htagged::new(ptr::<u64>::invalid(0xffff_8000_0000_0008), 3).unwrap().ptr().addr(), 0xffff_8000_0000_0008);
+}
+
+#[test]
+fn test_atomic_ptr() {
+  use pop::atomic::AtomicPtr;
+  use std::sync::atomic::Ordering::Relaxed;
+
+  let a = [0u64; 2];
+  let x = ptr::from(&a[0]);
+  let y = ptr::from(&a[1]);
+  let z = AtomicPtr::new(x);
+  assert_eq!(z.compare_exchange(y, x, Relaxed, Relaxed), Err(x));
+  assert_eq!(z.compare_exchange(x, y, Relaxed, Relaxed), Ok(x));
+  assert_eq!(z.fetch_or(1, Relaxed), y);
+  assert_eq!(z.fetch_and(! 1, Relaxed).addr(), y.addr() | 1);
+  assert_eq!(z.fetch_byte_sub(8, Relaxed), y);
+  assert_eq!(z.fetch_update(Relaxed, Relaxed, |p| Some(p + 1usize)), Ok(x));
+  assert_eq!(unsafe { z.load(Relaxed).read() }, 0);
+}
+
+#[test]
+fn test_atomic_ptr_counter() {
+  use pop::atomic::AtomicPtrCounter;
+
+  let a = 0u64;
+  let x = ptr::from(&a);
+  let z = AtomicPtrCounter::new(x, 0);
+  assert_eq!(z.compare_exchange((x, 1), (ptr::NULL, 2)), Err((x, 0)));
+
+  std::thread::scope(|s| {
+    for _ in 0 .. 4 {
+      let _ = s.spawn(|| {
+        for _ in 0 .. 1000 {
+          let mut y = z.load();
+          while let Err(w) = z.compare_exchange(y, (y.0, y.1 + 1)) {
+            y = w;
+          }
+        }
+      });
+    }
+  });
+
+  assert_eq!(z.load(), (x, 4000));
+
+  assert_eq!(z.swap((ptr::NULL, 0)), (x, 4000));
+  assert_eq!(z.swap((x, 5)), (ptr::NULL, 0));
+  z.store((ptr::NULL, 6));
+  assert_eq!(z.load(), (ptr::NULL, 6));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_cage() {
+  use pop::compressed::Cage;
+  use pop::compressed::cptr32;
+
+  struct Node {
+    next: cptr32<Node>,
+    value: u32,
+  }
+
+  assert_eq!(size_of::<Node>(), 8);
+
+  let cage = Cage::new(1 << 16);
+  let mut list = cptr32::<Node>::NULL;
+
+  for i in 0 .. 10 {
+    let x = cage.alloc::<Node>();
+    unsafe { cage.decompress(x).write(Node { next: list, value: i }) };
+    list = x;
+  }
+
+  let mut sum = 0;
+  let mut x = cage.decompress(list);
+
+  while ! x.is_null() {
+    assert_eq!(cage.compress(x).decompress(cage.base()), x);
+    let node = unsafe { x.as_ref() };
+    sum += node.value;
+    x = cage.decompress(node.next);
+  }
+
+  assert_eq!(sum, 45);
+  assert!(cage.try_alloc_layout::<u8>(std::alloc::Layout::new::<[u8; 1 << 16]>()).is_err());
+
+  let layout = std::alloc::Layout::from_size_align(64, 16384).unwrap();
+  let x = cage.try_alloc_layout::<u8>(layout).unwrap();
+  assert_eq!(cage.decompress(x).addr() % 16384, 0);
+  assert!(cage.contains(cage.decompress(x)));
+}
+
+#[cfg(feature = "reserve")]
+#[test]
+fn test_cage_reserve() {
+  use pop::compressed::Cage;
+  use std::alloc::Layout;
+
+  let cage = Cage::new(1 << 31);
+  assert_eq!(cage.committed(), 0);
+
+  let x = cage.alloc::<u64>();
+  unsafe { cage.decompress(x).write(1) };
+  assert_eq!(cage.committed(), 1 << 16);
+
+  let y = cage.alloc_layout::<u8>(Layout::from_size_align(1 << 30, 1).unwrap());
+  unsafe { cage.decompress(y).byte_add::<u8>((1 << 30) - 1).write(2) };
+  assert!(cage.committed() <= (1 << 30) + (2 << 16));
+  assert_eq!(unsafe { cage.decompress(x).read() }, 1);
+
+  cage.reset();
+  assert_eq!(cage.used(), 1);
+  assert!(cage.committed() > 1 << 30);
+}
+
+#[test]
+fn test_relptr() {
+  use pop::rel::relptr;
+
+  #[repr(C)]
+  struct Node {
+    next: relptr<Node>,
+    value: u64,
+  }
+
+  let mut a = [Node { next: relptr::NULL, value: 1 }, Node { next: relptr::NULL, value: 2 }];
+  let x = ptr::from(&mut a[..]);
+  unsafe { relptr::store(ptr::from(&mut (*x.as_mut_ptr()).next), x + 1usize) };
+  assert_eq!(a[0].next.get().addr(), ptr::from(&a[1]).addr());
+
+  let mut b = [0u8; 32];
+  unsafe { ptr::from(&mut b[..]).cast::<[Node; 2]>().write_unaligned(a) };
+  let y = ptr::from(&b[..]).cast::<Node>();
+  let z = unsafe { relptr::<Node>::resolve(y.cast()) };
+  assert_eq!(z, y.byte_add(16));
+  assert_eq!(unsafe { z.read_unaligned() }.value, 2);
+  assert!(unsafe { relptr::<Node>::resolve(z.cast()) }.is_null());
+}
+
+#[test]
+fn test_fptr() {
+  use pop::fptr;
+  use std::fmt::Debug;
+
+  trait Shape: Debug {
+    fn area(&self) -> u32;
+  }
+
+  #[derive(Debug)]
+  struct Square(u32);
+
+  impl Shape for Square {
+    fn area(&self) -> u32 {
+      return self.0 * self.0;
+    }
+  }
+
+  pop::dyn_pointee!(unsafe dyn Shape);
+
+  let a = [1u32, 2, 3];
+  let x = fptr::<[u32]>::from(&a[..]);
+  assert_eq!(x.len(), 3);
+  let (data, len) = x.to_raw_parts();
+  assert_eq!(fptr::<[u32]>::from_raw_parts(data, len), x);
+  assert_eq!(unsafe { x.as_ref() }, &[1, 2, 3]);
+
+  let b = Square(3);
+  let y = fptr::<dyn Shape>::from(&b as &dyn Shape);
+  let (data, vtable) = y.to_raw_parts();
+  let z = fptr::<dyn Shape>::from_raw_parts(data, vtable);
+  assert_eq!(z, y);
+  assert_eq!(unsafe { z.as_ref() }.area(), 9);
+  assert_eq!(z.cast::<Square>().addr(), ptr::from(&b).addr());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_thin() {
+  use pop::thin::thin_dyn;
+  use pop::thin::thin_slice;
+  use std::rc::Rc;
+
+  trait Shape {
+    fn area(&self) -> u32;
+  }
+
+  struct Rect(u32, u32, #[allow(dead_code)] Rc<()>);
+
+  impl Shape for Rect {
+    fn area(&self) -> u32 {
+      return self.0 * self.1;
+    }
+  }
+
+  pop::dyn_pointee!(unsafe dyn Shape);
+
+  assert_eq!(size_of::<thin_slice<u8>>(), size_of::<usize>());
+  assert_eq!(size_of::<thin_dyn<dyn Shape>>(), size_of::<usize>());
+
+  let x = thin_slice::from_slice(&[1u16, 2, 3]);
+  assert_eq!(unsafe { x.as_ref() }, &[1, 2, 3]);
+  unsafe { x.drop_and_dealloc() };
+
+  let rc = Rc::new(());
+  let y = unsafe { thin_dyn::<dyn Shape>::new(Rect(2, 3, rc.clone()), |x| x) };
+  assert_eq!(unsafe { y.as_ref() }.area(), 6);
+  assert_eq!(Rc::strong_count(&rc), 2);
+  unsafe { y.drop_and_dealloc() };
+  assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn test_project() {
+  struct Inner {
+    tag: u8,
+    pair: (u16, u64),
+  }
+
+  struct Outer {
+    id: u32,
+    inner: Inner,
+    items: [u32; 3],
+  }
+
+  let mut x = Outer { id: 1, inner: Inner { tag: 2, pair: (3, 4) }, items: [5, 6, 7] };
+  let p = ptr::from(&mut x);
+
+  let (id, tag, pair, item, inner) = unsafe {
+    let id: ptr<u32> = pop::project!(p, id);
+    let tag: ptr<u8> = pop::project!(p, inner.tag);
+    let pair: ptr<u64> = pop::project!(p, inner.pair.1);
+    let item: ptr<u32> = pop::project!(p, items[2]);
+    let inner = pop::container_of!(tag, Inner, tag);
+    (id, tag, pair, item, inner)
+  };
+
+  assert_eq!(unsafe { (id.read(), tag.read(), pair.read(), item.read()) }, (1, 2, 4, 7));
+  unsafe { pair.write(9) };
+  assert_eq!(x.inner.pair.1, 9);
+
+  assert_eq!(pop::container_of!(id, Outer, id), p);
+  assert_eq!(pop::container_of!(pair, Outer, inner.pair.1), p);
+  assert_eq!(pop::container_of!(inner, Outer, inner), p);
+}
+
+#[test]
+fn test_intrusive_list() {
+  use pop::intrusive::Link;
+  use pop::intrusive::List;
+
+  struct Task {
+    id: u32,
+    run: Link,
+    all: Link,
+  }
+
+  pop::intrusive_adapter!(struct Run = Task { run: Link });
+  pop::intrusive_adapter!(struct All = Task { all: Link });
+
+  let mut tasks = (0 .. 6).map(|id| Task { id, run: Link::new(), all: Link::new() }).collect::<Vec<_>>();
+  let xs = tasks.iter_mut().map(ptr::from).collect::<Vec<_>>();
+  let ids = |list: &List<Run>| list.iter().map(|x| unsafe { (*x.as_const_ptr()).id }).collect::<Vec<_>>();
+
+  let mut a = List::<Run>::new();
+  let mut b = List::<Run>::new();
+  let mut all = List::<All>::new();
+
+  for &x in &xs {
+    unsafe { all.push_back(x) };
+  }
+
+  unsafe { a.push_back(xs[1]) };
+  unsafe { a.push_back(xs[2]) };
+  unsafe { a.push_front(xs[0]) };
+  unsafe { b.push_back(xs[3]) };
+  unsafe { b.push_back(xs[4]) };
+  assert_eq!(ids(&a), [0, 1, 2]);
+
+  unsafe { a.remove(xs[1]) };
+  assert_eq!(ids(&a), [0, 2]);
+
+  let mut c = unsafe { a.cursor_mut_at(xs[0]) };
+  c.splice_after(&mut b);
+  unsafe { c.insert_before(xs[5]) };
+  assert_eq!(ids(&a), [5, 0, 3, 4, 2]);
+  assert!(b.is_empty());
+
+  a.append(&mut b);
+  assert_eq!(a.len(), 5);
+  assert_eq!(a.iter().next_back(), Some(xs[2]));
+  assert_eq!(all.len(), 6);
+  assert_eq!(all.pop_front(), Some(xs[0]));
+}
+
+#[test]
+fn test_intrusive_tree() {
+  use pop::intrusive::Tree;
+  use pop::intrusive::TreeLink;
+
+  struct Timer {
+    deadline: u64,
+    link: TreeLink,
+  }
+
+  pop::intrusive_adapter!(struct ByDeadline = Timer { link: TreeLink });
+
+  let deadline = |x: ptr<Timer>| unsafe { (*x.as_const_ptr()).deadline };
+  let mut timers = Vec::new();
+  let mut seed = 1u64;
+
+  for _ in 0 .. 1000 {
+    seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    timers.push(Timer { deadline: seed >> 54, link: TreeLink::new() });
+  }
+
+  let xs = timers.iter_mut().map(ptr::from).collect::<Vec<_>>();
+  let mut tree = Tree::<ByDeadline>::new();
+
+  for &x in &xs {
+    unsafe { tree.insert(x, |y| deadline(y).cmp(&deadline(x))) };
+  }
+
+  for &x in xs.iter().step_by(3) {
+    unsafe { tree.remove(x) };
+  }
+
+  let mut model = xs.iter().enumerate().filter(|(i, _)| i % 3 != 0).map(|(_, &x)| deadline(x)).collect::<Vec<_>>();
+  model.sort();
+
+  assert_eq!(tree.len(), model.len());
+  assert_eq!(tree.iter().map(deadline).collect::<Vec<_>>(), model);
+  assert_eq!(tree.iter().rev().map(deadline).collect::<Vec<_>>(), model.iter().rev().copied().collect::<Vec<_>>());
+
+  let k = model[model.len() / 2];
+  let lo = tree.lower_bound(|y| deadline(y).cmp(&k)).unwrap();
+  let hi = tree.upper_bound(|y| deadline(y).cmp(&k));
+  assert_eq!(deadline(lo), k);
+  assert_eq!(hi.map(deadline), model.iter().copied().find(|&d| d > k));
+
+  let mut cursor = unsafe { tree.cursor_at(lo) };
+  cursor.move_prev();
+  assert!(cursor.get().is_none_or(|y| deadline(y) < k));
+
+  while let Some(x) = tree.first() {
+    unsafe { tree.remove(x) };
+  }
+
+  assert!(tree.is_empty());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_raw_vec() {
+  use pop::arena::Bump;
+  use pop::raw::RawVec;
+
+  fn is_send_sync<T: Send + Sync>() {}
+  is_send_sync::<RawVec<u64>>();
+
+  let mut v = RawVec::<u32>::new();
+  let mut model = Vec::new();
+  let mut len = 0;
+  assert_eq!(v.capacity(), 0);
+
+  for i in 0 .. 100 {
+    v.reserve(len, 1);
+    unsafe { v.insert_at(len, len / 2, i) };
+    model.insert(len / 2, i);
+    len += 1;
+  }
+
+  assert!(v.capacity() >= 100);
+  assert_eq!(unsafe { core::slice::from_raw_parts(v.ptr().as_const_ptr(), len) }, &model[..]);
+
+  assert_eq!(unsafe { v.remove_at(len, 0) }, model.remove(0));
+  len -= 1;
+  assert_eq!(unsafe { v.remove_at(len, len - 1) }, model.pop().unwrap());
+  len -= 1;
+  assert_eq!(unsafe { v.remove_at(len, 10) }, model.remove(10));
+  len -= 1;
+  assert_eq!(unsafe { core::slice::from_raw_parts(v.ptr().as_const_ptr(), len) }, &model[..]);
+
+  v.shrink_to(len);
+  assert_eq!(v.capacity(), len);
+  v.shrink_to(0);
+  assert_eq!(v.capacity(), 0);
+
+  let mut z = RawVec::<()>::new();
+  assert_eq!(z.capacity(), usize::MAX);
+  z.reserve(usize::MAX - 1, 1);
+  unsafe { z.insert_at(0, 0, ()) };
+
+  let bump = Bump::new();
+  let mut w = RawVec::<u64, &Bump>::new_in(&bump);
+  w.reserve_exact(0, 3);
+  assert_eq!(w.capacity(), 3);
+  assert!(w.try_reserve(3, usize::MAX).is_err());
+}
+
+#[test]
+fn test_memory_ops() {
+  let mut a = [1u32, 2, 3, 4, 5, 6, 7, 8];
+  let p = ptr::from(&mut a[..]);
+
+  unsafe { (p + 2usize).copy_from(p, 5) };
+  assert_eq!(a, [1, 2, 1, 2, 3, 4, 5, 8]);
+  unsafe { (p + 2usize).copy_to(p, 5) };
+  assert_eq!(a, [1, 2, 3, 4, 5, 4, 5, 8]);
+  unsafe { p.byte_copy_from(p.byte_add::<u8>(4), 8) };
+  assert_eq!(a, [2, 3, 3, 4, 5, 4, 5, 8]);
+
+  unsafe { p.swap(p + 7usize) };
+  unsafe { p.swap(p) };
+  assert_eq!(a, [8, 3, 3, 4, 5, 4, 5, 2]);
+
+  let mut b = [0u32; 8];
+  let q = ptr::from(&mut b[..]);
+  unsafe { q.copy_from_nonoverlapping(p, 8) };
+  assert_eq!(unsafe { p.compare_bytes(q, 8) }, core::cmp::Ordering::Equal);
+  unsafe { q.byte_fill(0xff, 1) };
+  assert_eq!(unsafe { p.byte_compare(q, 32) }, core::cmp::Ordering::Less);
+
+  unsafe { q.read_volatile_to(p, 2) };
+  unsafe { (p + 2usize).write_volatile_from(q + 6usize, 2) };
+  assert_eq!(a, [u32::from_ne_bytes([0xff, 0, 0, 0]), 3, 5, 2, 5, 4, 5, 2]);
+
+  let mut v = [const { core::mem::MaybeUninit::<String>::uninit() }; 4];
+  let r = ptr::from(&mut v[..]).cast::<String>();
+  unsafe { r.fill("x".to_string(), 4) };
+  assert!(v.iter().all(|s| unsafe { s.assume_init_ref() } == "x"));
+  for s in &mut v {
+    unsafe { s.assume_init_drop() };
+  }
+}
+
+#[test]
+fn test_range() {
+  use pop::range;
+
+  let mut a = [0u32, 1, 2, 3, 4, 5, 6];
+  let r = range::from(&mut a[..]);
+
+  assert_eq!(r.len(), 7);
+  assert!(! r.is_empty());
+  assert!(r.contains(r.begin() + 6usize));
+  assert!(! r.contains(r.end()));
+  assert_eq!(r.iter().map(|x| unsafe { x.read() }).collect::<Vec<_>>(), [0, 1, 2, 3, 4, 5, 6]);
+  assert_eq!(r.iter().rev().map(|x| unsafe { x.read() }).collect::<Vec<_>>(), [6, 5, 4, 3, 2, 1, 0]);
+
+  let (x, y) = r.split_at(3);
+  assert_eq!((x.len(), y.len()), (3, 4));
+  assert_eq!(x.end(), y.begin());
+  assert_eq!(r.chunks(3).map(range::len).collect::<Vec<_>>(), [3, 3, 1]);
+  assert_eq!(r.chunks(3).len(), 3);
+  assert_eq!(unsafe { &*r.as_slice_const_ptr() }, &[0, 1, 2, 3, 4, 5, 6]);
+
+  let z = range::from_len(ptr::<[u64; 0]>::invalid(8), 5);
+  assert_eq!(z.len(), 5);
+  assert!(z.iter().all(|x| x.is_aligned()));
+  assert_eq!(z.iter().rev().count(), 5);
+  let (x, y) = z.split_at(2);
+  assert_eq!((x.len(), y.len(), y.begin()), (2, 3, z.begin()));
+  assert_eq!(z.chunks(2).map(range::len).collect::<Vec<_>>(), [2, 2, 1]);
+  assert_eq!(<*mut [[u64; 0]]>::from(z).len(), 5);
+  assert!(range::<()>::EMPTY.is_empty());
+
+  let h = range::from(&[(); usize::MAX][..]);
+  assert_eq!(h.len(), usize::MAX);
+  assert_eq!(h.iter().next_back(), Some(h.begin()));
+  assert_eq!(h.split_at(usize::MAX - 1).1.len(), 1);
+  assert_eq!(h.chunks(usize::MAX / 2 + 1).map(range::len).collect::<Vec<_>>(), [usize::MAX / 2 + 1, usize::MAX / 2]);
+  assert_eq!(unsafe { &*h.as_slice_const_ptr() }.len(), usize::MAX);
+}
+
+#[test]
+fn test_strided_pitched() {
+  use pop::pitched;
+  use pop::strided;
+
+  #[derive(Clone, Copy, Debug, PartialEq)]
+  #[repr(C)]
+  struct Pixel {
+    r: u8,
+    g: u8,
+    b: u8,
+    a: u8,
+  }
+
+  let mut image = [[0u32; 6]; 4];
+  for (y, row) in image.iter_mut().enumerate() {
+    for (x, v) in row.iter_mut().enumerate() {
+      *v = (10 * y + x) as u32;
+    }
+  }
+
+  let p = ptr::from(&mut image).cast::<u32>();
+  let view = pitched::new(p, 24, 5, 4);
+  assert_eq!(unsafe { view.at(4, 2).read() }, 24);
+  assert!(view.get(5, 0).is_none());
+  assert_eq!(view.column(3).iter(4).map(|x| unsafe { x.read() }).collect::<Vec<_>>(), [3, 13, 23, 33]);
+
+  let sub = view.sub(1, 1, 3, 2);
+  let rows = sub.rows().map(|r| r.map(|x| unsafe { x.read() }).collect::<Vec<_>>()).collect::<Vec<_>>();
+  assert_eq!(rows, [[11, 12, 13], [21, 22, 23]]);
+
+  let t = sub.transpose();
+  assert_eq!((t.width(), t.height()), (2, 3));
+  assert_eq!(unsafe { t.at(1, 2).read() }, 23);
+  let columns = sub.columns().rev().map(|c| c.map(|x| unsafe { x.read() }).collect::<Vec<_>>()).collect::<Vec<_>>();
+  assert_eq!(columns, [[13, 23], [12, 22], [11, 21]]);
+
+  let mut pixels = [Pixel { r: 1, g: 2, b: 3, a: 4 }; 3];
+  let greens = strided::new(ptr::from(&mut pixels[..]).byte_add::<u8>(1), 4);
+  for x in greens.iter(3) {
+    unsafe { x.write(9) };
+  }
+  assert!(pixels.iter().all(|p| *p == Pixel { r: 1, g: 9, b: 3, a: 4 }));
+
+  let s = strided::dense(p);
+  assert_eq!(s.rev(3).iter(3).map(|x| unsafe { x.read() }).collect::<Vec<_>>(), [2, 1, 0]);
+  assert_eq!(s.iter(3).rev().map(|x| unsafe { x.read() }).collect::<Vec<_>>(), [2, 1, 0]);
+}
+
+#[test]
+fn test_endian() {
+  use pop::be;
+  use pop::le;
+
+  let mut buf = [0u8; 32];
+  let p = ptr::from(&mut buf[..]);
+
+  unsafe { (p + 1usize).write_le(0x0102_0304u32) };
+  unsafe { (p + 5usize).write_be(0x0102_0304u32) };
+  assert_eq!(buf[1 .. 9], [4, 3, 2, 1, 1, 2, 3, 4]);
+  assert_eq!(unsafe { (p + 1usize).read_le::<u32>() }, 0x0102_0304);
+  assert_eq!(unsafe { (p + 5usize).read_be::<u32>() }, 0x0102_0304);
+  assert_eq!(unsafe { (p + 5usize).read_le::<u32>() }, 0x0403_0201);
+
+  unsafe { (p + 11usize).write_be(-1.5f64) };
+  assert_eq!(buf[11 .. 19], (-1.5f64).to_be_bytes());
+  assert_eq!(unsafe { (p + 11usize).read_be::<f64>() }, -1.5);
+  unsafe { (p + 19usize).write_le(-7i16) };
+  assert_eq!(unsafe { (p + 19usize).read_le::<i16>() }, -7);
+
+  assert_eq!(align_of::<le<u64>>(), 1);
+  assert_eq!(size_of::<be<u64>>(), 8);
+
+  let q = (p + 3usize).cast::<be<u32>>();
+  unsafe { q.write_native(0xdead_beef) };
+  assert_eq!(buf[3 .. 7], [0xde, 0xad, 0xbe, 0xef]);
+  assert_eq!(unsafe { q.read_native() }, 0xdead_beef);
+  assert_eq!(unsafe { q.cast::<le<u32>>().read() }.get(), 0xefbe_adde);
+
+  let mut x = le::new(1.25f32);
+  assert_eq!(x.get(), 1.25);
+  x.set(2.5);
+  assert_eq!(x, le::from(2.5));
+
+  let a = f32::from_bits(0x7f80_0001);
+  let b = f64::from_bits(0x7ff0_0000_0000_0001);
+  unsafe { (p + 1usize).write_be(a) };
+  unsafe { (p + 5usize).write_le(a) };
+  unsafe { (p + 9usize).write_be(b) };
+  unsafe { (p + 17usize).write_le(b) };
+  assert_eq!(unsafe { (p + 1usize).read_be::<f32>() }.to_bits(), 0x7f80_0001);
+  assert_eq!(unsafe { (p + 5usize).read_le::<f32>() }.to_bits(), 0x7f80_0001);
+  assert_eq!(unsafe { (p + 9usize).read_be::<f64>() }.to_bits(), 0x7ff0_0000_0000_0001);
+  assert_eq!(unsafe { (p + 17usize).read_le::<f64>() }.to_bits(), 0x7ff0_0000_0000_0001);
+  assert_eq!(be::new(a).get().to_bits(), 0x7f80_0001);
+  assert_eq!(le::new(b).get().to_bits(), 0x7ff0_0000_0000_0001);
+}
+
+#[test]
+fn test_cursor() {
+  use pop::cursor::Error;
+  use pop::cursor::Reader;
+  use pop::cursor::Writer;
+
+  let mut buf = [0xaau8; 64];
+  let p = ptr::from(&mut buf[..]);
+
+  let mut w = unsafe { Writer::new(p + 1usize, 40) };
+  w.write_le(0x1234u16).unwrap();
+  w.write_be(1.5f32).unwrap();
+  w.align_to(8).unwrap();
+  assert_eq!(w.position().addr() % 8, 0);
+  w.write_uleb128(624485).unwrap();
+  w.write_sleb128(-123456).unwrap();
+  w.write_sleb128(i64::MIN).unwrap();
+  w.write_prefixed_uleb128(b"hello").unwrap();
+  let before = w.offset();
+  assert_eq!(w.write_prefixed_le::<u8>(&[0; 300]), Err(Error::Overflow));
+  assert_eq!(w.offset(), before);
+  assert!(matches!(w.write_prefixed_be::<u32>(&[0; 20]), Err(Error::Overrun { .. })));
+  assert_eq!(w.offset(), before);
+  w.write_prefixed_be::<u16>(b"ab").unwrap();
+  let n = w.offset();
+
+  let mut r = unsafe { Reader::from_range(w.written()) };
+  assert_eq!(r.peek::<u8>(), Ok(0x34));
+  assert_eq!(r.read_le::<u16>(), Ok(0x1234));
+  assert_eq!(r.read_be::<f32>(), Ok(1.5));
+  r.align_to(8).unwrap();
+  assert_eq!(r.read_uleb128(), Ok(624485));
+  assert_eq!(r.read_sleb128(), Ok(-123456));
+  assert_eq!(r.read_sleb128(), Ok(i64::MIN));
+  let s = r.read_prefixed_uleb128().unwrap();
+  assert_eq!(unsafe { &*s.as_slice_const_ptr() }, b"hello");
+  let s = r.read_prefixed_be::<u16>().unwrap();
+  assert_eq!(unsafe { &*s.as_slice_const_ptr() }, b"ab");
+  assert_eq!(r.offset(), n);
+  assert!(r.is_empty());
+  assert_eq!(r.read::<u32>(), Err(Error::Overrun { needed: 4, remaining: 0 }));
+
+  let overlong = [0xffu8; 11];
+  let mut r = unsafe { Reader::new(ptr::from(&overlong[..]), 11) };
+  assert_eq!(r.read_uleb128(), Err(Error::Overflow));
+  assert_eq!(r.offset(), 0);
+  let mut r = unsafe { Reader::new(ptr::from(&overlong[..]), 3) };
+  assert!(matches!(r.read_sleb128(), Err(Error::Overrun { .. })));
+  assert_eq!(r.offset(), 0);
+  r.skip(3).unwrap();
+  assert!(r.skip(1).is_err());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_sanitizer() {
+  use pop::Allocator;
+  use pop::debug::Kind;
+  use pop::debug::Sanitizer;
+  use std::alloc::Layout;
+  use std::panic::AssertUnwindSafe;
+  use std::panic::catch_unwind;
+
+  let heap = Sanitizer::with_quarantine(64);
+
+  let a = unsafe { heap.alloc_slice::<u32>(4) }.unwrap();
+  let b = unsafe { heap.alloc::<u64>() }.unwrap();
+  assert_eq!(heap.live(), 2);
+  assert_eq!(unsafe { a.cast::<u8>().read() }, pop::debug::UNINIT);
+  assert!(heap.verify_heap().is_ok());
+
+  let end = (a + 4usize).cast::<u8>();
+  unsafe { end.write(0) };
+  let report = heap.verify_heap().unwrap_err();
+  assert_eq!(report.kind, Kind::BufferOverflow);
+  assert_eq!((report.address, report.at), (a.cast(), end));
+  assert_eq!(report.layout, Layout::new::<[u32; 4]>());
+  unsafe { end.write(pop::debug::CANARY) };
+
+  unsafe { (b.cast::<u8>() - 1usize).write(0) };
+  assert_eq!(heap.verify_heap().unwrap_err().kind, Kind::BufferUnderflow);
+  unsafe { (b.cast::<u8>() - 1usize).write(pop::debug::CANARY) };
+
+  unsafe { heap.dealloc(b) };
+  assert_eq!((heap.live(), heap.quarantined()), (1, 1));
+  unsafe { b.write(7) };
+  let report = heap.verify_heap().unwrap_err();
+  assert_eq!(report.kind, Kind::UseAfterFree);
+  assert_eq!(report.layout, Layout::new::<u64>());
+  unsafe { b.write_bytes(pop::debug::POISON, 1) };
+
+  let e = catch_unwind(AssertUnwindSafe(|| unsafe { heap.dealloc(b) })).unwrap_err();
+  assert!(e.downcast_ref::<String>().unwrap().starts_with("double free"));
+
+  let e = catch_unwind(AssertUnwindSafe(|| unsafe { heap.dealloc_slice(a, 3) })).unwrap_err();
+  assert!(e.downcast_ref::<String>().unwrap().contains("freed with size 12"));
+
+  let a = unsafe { heap.realloc_slice(a, 4, 8) }.unwrap();
+  unsafe { heap.dealloc_slice(a, 8) };
+  assert_eq!(heap.live(), 0);
+  assert!(heap.verify_heap().is_ok());
+}
+
+#[cfg(feature = "checked")]
+#[test]
+fn test_checked() {
+  use pop::global;
+  use std::panic::catch_unwind;
+
+  fn message(f: impl FnOnce() + std::panic::UnwindSafe) -> String {
+    return *catch_unwind(f).unwrap_err().downcast::<String>().unwrap();
+  }
+
+  let x = unsafe { global::alloc_slice::<u64>(4) };
+  let e = message(|| unsafe { global::dealloc_slice(x, 3) });
+  assert!(e.contains("layout mismatch"), "{}", e);
+  assert!(e.contains("size 24") && e.contains("allocated with size 32"), "{}", e);
+
+  let e = message(|| { let _ = unsafe { global::realloc_slice(x, 5, 8) }; });
+  assert!(e.contains("layout mismatch") && e.contains("reallocating"), "{}", e);
+
+  let x = unsafe { global::realloc_slice(x, 4, 8) };
+  unsafe { global::dealloc_slice(x, 8) };
+  let e = message(|| unsafe { global::dealloc_slice(x, 8) });
+  assert!(e.contains("double free"), "{}", e);
+
+  let y = 0u64;
+  let e = message(|| unsafe { global::dealloc(ptr::from(&y)) });
+  assert!(e.contains("unknown pointer"), "{}", e);
+
+  let v = (0 .. 100).map(|_| unsafe { global::alloc::<u64>() }).collect::<Vec<_>>();
+  for &z in &v {
+    unsafe { global::dealloc(z) };
+  }
+  let e = message(|| unsafe { global::dealloc(v[0]) });
+  assert!(e.contains("unknown pointer"), "{}", e);
+  let e = message(|| unsafe { global::dealloc(v[99]) });
+  assert!(e.contains("double free"), "{}", e);
+}